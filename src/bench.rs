@@ -0,0 +1,518 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    input,
+    json::{self, Json},
+    memory::{self, Usage},
+    runner::RunError,
+    solution::{get_solution, Part},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Solve(Part),
+}
+
+impl Stage {
+    fn name(&self) -> &'static str {
+        match self {
+            Stage::Parse => "parse",
+            Stage::Solve(Part::One) => "part1",
+            Stage::Solve(Part::Two) => "part2",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Stage> {
+        match name {
+            "parse" => Some(Stage::Parse),
+            "part1" => Some(Stage::Solve(Part::One)),
+            "part2" => Some(Stage::Solve(Part::Two)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Solve(part) => write!(f, "part {part}"),
+        }
+    }
+}
+
+pub struct Options {
+    pub warmup: u32,
+    pub iterations: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// Panics on an empty sample.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        let mean = sorted.iter().map(|d| d.as_secs_f64()).sum::<f64>() / sorted.len() as f64;
+        let variance = sorted
+            .iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>()
+            / sorted.len() as f64;
+        Stats {
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    pub day: u32,
+    pub stage: Stage,
+    pub stats: Stats,
+    pub answer: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    Run(RunError),
+    UnstableAnswer(u32, Part),
+    Baseline(PathBuf, String),
+    /// [`memory::Counting`] is not the global allocator.
+    NotCounting,
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BenchError::Run(error) => write!(f, "{error}"),
+            BenchError::UnstableAnswer(day, part) => {
+                write!(
+                    f,
+                    "day {day} part {part} gave different answers between runs"
+                )
+            }
+            BenchError::Baseline(path, reason) => {
+                write!(f, "baseline {}: {reason}", path.display())
+            }
+            BenchError::NotCounting => write!(
+                f,
+                "allocations are only counted in builds with --features alloc-count"
+            ),
+        }
+    }
+}
+
+// Times `iterations` runs of `f` after `warmup` untimed ones.
+fn time<T>(options: &Options, mut f: impl FnMut() -> T) -> (Stats, Vec<T>) {
+    for _ in 0..options.warmup {
+        f();
+    }
+    let mut samples: Vec<Duration> = Vec::new();
+    let mut results: Vec<T> = Vec::new();
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        let result = f();
+        samples.push(start.elapsed());
+        results.push(result);
+    }
+    (Stats::from_samples(&samples), results)
+}
+
+/// Times parsing and both parts of a day separately, parts that are not solved yet are skipped.
+/// Every timed run has to give the same answer.
+pub fn measure(day: u32, input: &Path, options: &Options) -> Result<Vec<Measurement>, BenchError> {
+    let solution = get_solution(day).ok_or(BenchError::Run(RunError::UnknownDay(day)))?;
+    let input = input::read(input)
+        .map_err(|_| BenchError::Run(RunError::MissingInput(input.to_owned())))?;
+
+    let (stats, _) = time(options, || solution.parse(&input));
+    let parsed = solution
+        .parse(&input)
+        .map_err(|error| BenchError::Run(RunError::InvalidInput(error)))?;
+    let mut measurements = vec![Measurement {
+        day,
+        stage: Stage::Parse,
+        stats,
+        answer: None,
+    }];
+
+    for part in [Part::One, Part::Two] {
+        let (stats, answers) = time(options, || solution.solve(parsed.as_ref(), part));
+        let Some(answer) = answers.first().cloned().flatten() else {
+            continue;
+        };
+        if answers.windows(2).any(|pair| pair[0] != pair[1]) {
+            return Err(BenchError::UnstableAnswer(day, part));
+        }
+        measurements.push(Measurement {
+            day,
+            stage: Stage::Solve(part),
+            stats,
+            answer: Some(answer),
+        });
+    }
+    Ok(measurements)
+}
+
+/// The heap usage of one stage of a day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Allocation {
+    pub day: u32,
+    pub stage: Stage,
+    pub usage: Usage,
+}
+
+/// Counts the allocations of parsing and both parts of a day in a single run each, parts that are
+/// not solved yet are skipped. Fails unless [`memory::Counting`] is the global allocator.
+pub fn measure_allocations(day: u32, input: &Path) -> Result<Vec<Allocation>, BenchError> {
+    if !memory::is_counting() {
+        return Err(BenchError::NotCounting);
+    }
+    let solution = get_solution(day).ok_or(BenchError::Run(RunError::UnknownDay(day)))?;
+    let input = input::read(input)
+        .map_err(|_| BenchError::Run(RunError::MissingInput(input.to_owned())))?;
+
+    let (usage, parsed) = memory::measure(|| solution.parse(&input));
+    let parsed = parsed.map_err(|error| BenchError::Run(RunError::InvalidInput(error)))?;
+    let mut allocations = vec![Allocation {
+        day,
+        stage: Stage::Parse,
+        usage,
+    }];
+    for part in [Part::One, Part::Two] {
+        let (usage, answer) = memory::measure(|| solution.solve(parsed.as_ref(), part));
+        if answer.is_some() {
+            allocations.push(Allocation {
+                day,
+                stage: Stage::Solve(part),
+                usage,
+            });
+        }
+    }
+    Ok(allocations)
+}
+
+fn nanos(duration: Duration) -> Json {
+    Json::Number(duration.as_nanos() as f64)
+}
+
+pub fn to_json(options: &Options, measurements: &[Measurement]) -> Json {
+    let results = measurements
+        .iter()
+        .map(|measurement| {
+            Json::Object(vec![
+                ("day".to_owned(), Json::Number(measurement.day as f64)),
+                (
+                    "stage".to_owned(),
+                    Json::String(measurement.stage.name().to_owned()),
+                ),
+                ("min_ns".to_owned(), nanos(measurement.stats.min)),
+                ("median_ns".to_owned(), nanos(measurement.stats.median)),
+                ("mean_ns".to_owned(), nanos(measurement.stats.mean)),
+                ("stddev_ns".to_owned(), nanos(measurement.stats.stddev)),
+                (
+                    "answer".to_owned(),
+                    measurement.answer.clone().map_or(Json::Null, Json::String),
+                ),
+            ])
+        })
+        .collect();
+    Json::Object(vec![
+        ("warmup".to_owned(), Json::Number(options.warmup as f64)),
+        (
+            "iterations".to_owned(),
+            Json::Number(options.iterations as f64),
+        ),
+        ("results".to_owned(), Json::Array(results)),
+    ])
+}
+
+fn from_json(baseline: &Json) -> Option<Vec<Measurement>> {
+    let duration =
+        |result: &Json, key: &str| Some(Duration::from_nanos(result.get(key)?.as_f64()? as u64));
+    baseline
+        .get("results")?
+        .as_array()?
+        .iter()
+        .map(|result| {
+            Some(Measurement {
+                day: result.get("day")?.as_f64()? as u32,
+                stage: Stage::from_name(result.get("stage")?.as_str()?)?,
+                stats: Stats {
+                    min: duration(result, "min_ns")?,
+                    median: duration(result, "median_ns")?,
+                    mean: duration(result, "mean_ns")?,
+                    stddev: duration(result, "stddev_ns")?,
+                },
+                answer: result.get("answer")?.as_str().map(|s| s.to_owned()),
+            })
+        })
+        .collect()
+}
+
+pub fn save_baseline(
+    path: &Path,
+    options: &Options,
+    measurements: &[Measurement],
+) -> Result<(), BenchError> {
+    fs::write(path, format!("{}\n", to_json(options, measurements)))
+        .map_err(|error| BenchError::Baseline(path.to_owned(), error.to_string()))
+}
+
+pub fn load_baseline(path: &Path) -> Result<Vec<Measurement>, BenchError> {
+    let baseline_error = |reason: String| BenchError::Baseline(path.to_owned(), reason);
+    let baseline = fs::read_to_string(path).map_err(|error| baseline_error(error.to_string()))?;
+    let baseline = json::parse(&baseline).map_err(baseline_error)?;
+    from_json(&baseline).ok_or_else(|| baseline_error("not a benchmark baseline".to_owned()))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Unchanged,
+    Faster,
+    Slower,
+    AnswerChanged,
+}
+
+impl Verdict {
+    pub fn is_regression(&self) -> bool {
+        matches!(self, Verdict::Slower | Verdict::AnswerChanged)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Unchanged => write!(f, "ok"),
+            Verdict::Faster => write!(f, "faster"),
+            Verdict::Slower => write!(f, "REGRESSION"),
+            Verdict::AnswerChanged => write!(f, "ANSWER CHANGED"),
+        }
+    }
+}
+
+pub struct Comparison {
+    pub day: u32,
+    pub stage: Stage,
+    pub baseline: Duration,
+    pub current: Duration,
+    /// Change of the median in percent, positive when slower.
+    pub change: f64,
+    pub verdict: Verdict,
+}
+
+/// Compares the medians of the stages measured in both runs, `threshold` is in percent.
+pub fn compare(
+    baseline: &[Measurement],
+    current: &[Measurement],
+    threshold: f64,
+) -> Vec<Comparison> {
+    current
+        .iter()
+        .filter_map(|measurement| {
+            let before = baseline.iter().find(|before| {
+                before.day == measurement.day && before.stage == measurement.stage
+            })?;
+            let (old, new) = (before.stats.median, measurement.stats.median);
+            let change =
+                (new.as_secs_f64() / old.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0;
+            let verdict = if before.answer != measurement.answer {
+                Verdict::AnswerChanged
+            } else if change > threshold {
+                Verdict::Slower
+            } else if change < -threshold {
+                Verdict::Faster
+            } else {
+                Verdict::Unchanged
+            };
+            Some(Comparison {
+                day: measurement.day,
+                stage: measurement.stage,
+                baseline: old,
+                current: new,
+                change,
+                verdict,
+            })
+        })
+        .collect()
+}
+
+pub fn format_measurements(measurements: &[Measurement]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        "day", "stage", "min", "median", "mean", "stddev"
+    );
+    for measurement in measurements {
+        let stats = measurement.stats;
+        table += &format!(
+            "{:>3}  {:<6}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+            measurement.day,
+            measurement.stage.to_string(),
+            format!("{:.2?}", stats.min),
+            format!("{:.2?}", stats.median),
+            format!("{:.2?}", stats.mean),
+            format!("{:.2?}", stats.stddev),
+        );
+    }
+    table
+}
+
+pub fn format_allocations(allocations: &[Allocation]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<6}  {:>12}  {:>10}  {:>10}\n",
+        "day", "stage", "allocations", "bytes", "peak"
+    );
+    for allocation in allocations {
+        let usage = allocation.usage;
+        table += &format!(
+            "{:>3}  {:<6}  {:>12}  {:>10}  {:>10}\n",
+            allocation.day,
+            allocation.stage.to_string(),
+            usage.allocations,
+            memory::format_bytes(usage.bytes),
+            memory::format_bytes(usage.peak),
+        );
+    }
+    table
+}
+
+pub fn format_comparisons(comparisons: &[Comparison]) -> String {
+    let mut table = format!(
+        "{:>3}  {:<6}  {:>10}  {:>10}  {:>8}  verdict\n",
+        "day", "stage", "baseline", "median", "change"
+    );
+    for comparison in comparisons {
+        table += &format!(
+            "{:>3}  {:<6}  {:>10}  {:>10}  {:>7.1}%  {}\n",
+            comparison.day,
+            comparison.stage.to_string(),
+            format!("{:.2?}", comparison.baseline),
+            format!("{:.2?}", comparison.current),
+            comparison.change,
+            comparison.verdict,
+        );
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn measurement(stage: Stage, median_ms: u64, answer: Option<&str>) -> Measurement {
+        let median = Duration::from_millis(median_ms);
+        Measurement {
+            day: 6,
+            stage,
+            stats: Stats {
+                min: median,
+                median,
+                mean: median,
+                stddev: Duration::ZERO,
+            },
+            answer: answer.map(|s| s.to_owned()),
+        }
+    }
+
+    #[test]
+    fn bench_stats_from_samples() {
+        let samples: Vec<Duration> = [4, 1, 3, 2]
+            .iter()
+            .map(|ms| Duration::from_millis(*ms))
+            .collect();
+        let stats = Stats::from_samples(&samples);
+        assert_eq!(Duration::from_millis(1), stats.min);
+        assert_eq!(Duration::from_micros(2500), stats.median);
+        assert_eq!(Duration::from_micros(2500), stats.mean);
+        assert_eq!(1118, stats.stddev.as_micros());
+    }
+
+    #[test]
+    fn bench_baseline_round_trip() {
+        let options = Options {
+            warmup: 1,
+            iterations: 3,
+        };
+        let measurements = vec![
+            measurement(Stage::Parse, 1, None),
+            measurement(Stage::Solve(Part::Two), 7, Some("71503")),
+        ];
+        let baseline = json::parse(&to_json(&options, &measurements).to_string()).unwrap();
+        assert_eq!(Some(measurements), from_json(&baseline));
+    }
+
+    #[test]
+    fn bench_compare_reports_regressions() {
+        let baseline = vec![
+            measurement(Stage::Parse, 10, None),
+            measurement(Stage::Solve(Part::One), 10, Some("288")),
+            measurement(Stage::Solve(Part::Two), 10, Some("71503")),
+        ];
+        let current = vec![
+            measurement(Stage::Parse, 12, None),
+            measurement(Stage::Solve(Part::One), 5, Some("288")),
+            measurement(Stage::Solve(Part::Two), 10, Some("71504")),
+        ];
+        let verdicts: Vec<Verdict> = compare(&baseline, &current, 10.0)
+            .iter()
+            .map(|comparison| comparison.verdict)
+            .collect();
+        assert_eq!(
+            vec![Verdict::Slower, Verdict::Faster, Verdict::AnswerChanged],
+            verdicts
+        );
+        assert!(compare(&baseline, &current, 25.0)[0].verdict == Verdict::Unchanged);
+    }
+
+    #[test]
+    fn bench_measures_example_input() {
+        let options = Options {
+            warmup: 0,
+            iterations: 2,
+        };
+        let measurements = measure(6, Path::new("./src/day6/example_input.txt"), &options).unwrap();
+        let stages: Vec<Stage> = measurements.iter().map(|m| m.stage).collect();
+        assert_eq!(
+            vec![
+                Stage::Parse,
+                Stage::Solve(Part::One),
+                Stage::Solve(Part::Two)
+            ],
+            stages
+        );
+        assert_eq!(Some("71503".to_owned()), measurements[2].answer);
+    }
+
+    #[test]
+    fn bench_counts_allocations_of_example_input() {
+        // The test binary counts with the allocator installed in lib.rs.
+        let allocations =
+            measure_allocations(6, Path::new("./src/day6/example_input.txt")).unwrap();
+        assert_eq!(3, allocations.len());
+        assert!(allocations[0].usage.allocations > 0);
+        let table = format_allocations(&allocations);
+        assert!(table.starts_with("day  stage    allocations"));
+        assert_eq!(4, table.lines().count());
+    }
+}
@@ -0,0 +1,833 @@
+use std::{fmt, mem, path::PathBuf};
+
+use aoc_2023::{image::ImageFormat, log::Level, runner::Format};
+
+pub const USAGE: &str = "Usage: aoc_2023 [-v | -q] <command> [options]
+
+Commands:
+  run           Solve a day or all of them, both parts unless --part is given
+  bench         Time parsing and both parts of a day, or count their allocations, every day
+                unless --day is given
+  verify        Check the answers listed in the manifest
+  differential  Compare the alternative solvers of days 9, 14 and 18 on generated inputs
+  generate      Print a random input for a day (9, 12, 14, 18, 19, 20, 22 or 24)
+  render        Draw the map of a day (10, 11, 14, 16, 17, 18, 21 or 23) with what the solver
+                found, in the terminal or as an image
+  frames        Write every step of a simulation (day 14, 20, 21 or 22) as numbered frames
+  help          Print this message
+
+Global options, allowed anywhere:
+  -v, --verbose         Log diagnostics of the solvers to stderr, repeat for more detail
+  -q, --quiet           Only report errors
+
+Options for run:
+  -d, --day <DAY>       Day to solve (1-24)
+  -a, --all             Solve every day on its default input in parallel, slowest first
+  -p, --part <PART>     Part to solve (1 or 2)
+  -i, --input <PATH>    Puzzle input, defaults to ./src/day<DAY>/input.txt, - reads stdin
+  -f, --format <FMT>    Output as text, json or csv with input path and timings, defaults to text
+  -b, --bag <BAG>       Cubes in the bag of day 2 like '12 red, 13 green, 14 blue', or a file
+                        listing them, part two multiplies the colours of the bag
+      --vocabulary <NAME>
+                        Spelled digits of day 1 part two, one of english, german, french,
+                        spanish or roman, or a file of 'word digit' lines, defaults to english
+
+Options for bench:
+  -d, --day <DAY>          Day to benchmark (1-24)
+  -w, --warmup <N>         Untimed runs before measuring, defaults to 1
+  -n, --iterations <N>     Timed runs, defaults to 10
+  -s, --save <PATH>        Store the results as a JSON baseline
+  -b, --baseline <PATH>    Compare the medians against a saved baseline
+  -t, --threshold <PCT>    Slowdown reported as regression, defaults to 10
+  -a, --alloc              Count heap allocations, bytes and peak memory of a single run
+                           instead of timing, needs a build with --features alloc-count
+
+Options for verify:
+  -m, --manifest <PATH>    Answer manifest, defaults to ./answers.txt
+  -d, --day <DAY>          Only check this day
+
+Options for differential:
+  -d, --day <DAY>          Only compare the solvers of this day
+  -s, --seeds <N>          Inputs per size, defaults to 20
+  -n, --size <N>           Largest generated size, defaults to 12
+
+Options for generate:
+  -d, --day <DAY>       Day to generate an input for
+  -s, --seed <SEED>     Seed of the random generator, defaults to 0
+  -n, --size <N>        Number of lines, columns, workflows, counters or bricks, defaults to
+                        the size of the actual puzzle
+
+Options for render:
+  -d, --day <DAY>       Day to draw
+  -i, --input <PATH>    Puzzle input, defaults to ./src/day<DAY>/input.txt, - reads stdin
+  -n, --steps <N>       Steps of day 21 or spin cycles of day 14, defaults to the puzzle's
+  -o, --output <PATH>   Write an image instead, the extension picks ppm, png or svg
+  -s, --scale <N>       Pixels per tile of the image, defaults to 4
+      --plain           Mark tiles with symbols instead of colours, also when NO_COLOR is set
+
+Options for frames:
+  -d, --day <DAY>       Day to simulate
+  -i, --input <PATH>    Puzzle input, defaults to ./src/day<DAY>/input.txt, - reads stdin
+  -n, --steps <N>       Spin cycles of day 14 (3), button presses of day 20 (1) or steps of
+                        day 21 (64), day 22 always lets every brick fall
+  -o, --output <DIR>    Directory for the frames, defaults to ./frames/day<DAY>
+  -f, --format <FMT>    Frames as text, ppm, png or svg, defaults to text
+  -s, --scale <N>       Pixels per tile of image frames, defaults to 4
+
+Exit codes:
+  0  success
+  1  the input, baseline or manifest could not be read, or the image or frames not written
+  2  invalid command line
+  3  the requested day or part does not exist
+  4  the input could not be parsed
+  5  a benchmark regressed against the baseline
+  6  an answer does not match the manifest or alternative solvers disagree
+  7  a solver panicked";
+
+pub struct RunArgs {
+    /// `None` solves every day (`--all`).
+    pub day: Option<u32>,
+    pub part: Option<u32>,
+    pub input: Option<PathBuf>,
+    pub format: Format,
+    /// Day 2 only, the cubes or a file with them.
+    pub bag: Option<String>,
+    /// Day 1 only, a preset name or a file with the words.
+    pub vocabulary: Option<String>,
+}
+
+pub struct BenchArgs {
+    pub day: Option<u32>,
+    pub warmup: u32,
+    pub iterations: u32,
+    pub save: Option<PathBuf>,
+    pub baseline: Option<PathBuf>,
+    pub threshold: u32,
+    pub alloc: bool,
+}
+
+pub struct VerifyArgs {
+    pub manifest: Option<PathBuf>,
+    pub day: Option<u32>,
+}
+
+pub struct DifferentialArgs {
+    pub day: Option<u32>,
+    pub seeds: u32,
+    pub size: u32,
+}
+
+pub struct GenerateArgs {
+    pub day: u32,
+    pub seed: u64,
+    /// `None` for the size of the actual puzzle.
+    pub size: Option<u32>,
+}
+
+pub struct RenderArgs {
+    pub day: u32,
+    pub input: Option<PathBuf>,
+    pub steps: Option<usize>,
+    /// `None` draws in the terminal.
+    pub output: Option<(PathBuf, ImageFormat)>,
+    pub scale: usize,
+    pub plain: bool,
+}
+
+pub struct FramesArgs {
+    pub day: u32,
+    pub input: Option<PathBuf>,
+    pub steps: Option<usize>,
+    pub output: Option<PathBuf>,
+    /// `None` writes text frames.
+    pub format: Option<ImageFormat>,
+    pub scale: usize,
+}
+
+pub enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Verify(VerifyArgs),
+    Differential(DifferentialArgs),
+    Generate(GenerateArgs),
+    Render(RenderArgs),
+    Frames(FramesArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CliError {
+    MissingCommand,
+    UnknownCommand(String),
+    UnknownOption(String),
+    MissingValue(String),
+    InvalidNumber(String, String),
+    UnknownFormat(String),
+    UnknownImageFormat(PathBuf),
+    UnknownFrameFormat(String),
+    Conflict(String, String),
+    OnlyForDay(String, u32),
+    MissingFeature(String, String),
+    MissingDay,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::MissingCommand => write!(f, "no command given"),
+            CliError::UnknownCommand(command) => write!(f, "unknown command '{command}'"),
+            CliError::UnknownOption(option) => write!(f, "unknown option '{option}'"),
+            CliError::MissingValue(option) => write!(f, "option '{option}' needs a value"),
+            CliError::InvalidNumber(option, value) => {
+                write!(f, "option '{option}' expects a number, got '{value}'")
+            }
+            CliError::UnknownFormat(format) => {
+                write!(f, "unknown format '{format}', expected text, json or csv")
+            }
+            CliError::UnknownImageFormat(path) => write!(
+                f,
+                "cannot tell the image format of '{}', expected a ppm, png or svg extension",
+                path.display()
+            ),
+            CliError::UnknownFrameFormat(format) => {
+                write!(
+                    f,
+                    "unknown frame format '{format}', expected text, ppm, png or svg"
+                )
+            }
+            CliError::Conflict(one, other) => {
+                write!(f, "options '{one}' and '{other}' cannot be combined")
+            }
+            CliError::OnlyForDay(option, day) => {
+                write!(f, "option '{option}' only applies to day {day}")
+            }
+            CliError::MissingFeature(option, feature) => {
+                write!(
+                    f,
+                    "option '{option}' needs a build with --features {feature}"
+                )
+            }
+            CliError::MissingDay => write!(f, "missing --day"),
+        }
+    }
+}
+
+// Splits "--day=17" into ("--day", Some("17")), leaves "--day" untouched.
+fn split_option(arg: &str) -> (String, Option<String>) {
+    if arg.starts_with("--") {
+        if let Some((option, value)) = arg.split_once('=') {
+            return (option.to_owned(), Some(value.to_owned()));
+        }
+    }
+    (arg.to_owned(), None)
+}
+
+fn parse_number(option: &str, value: &str) -> Result<u32, CliError> {
+    value
+        .parse::<u32>()
+        .map_err(|_| CliError::InvalidNumber(option.to_owned(), value.to_owned()))
+}
+
+fn parse_run_args(args: &mut impl Iterator<Item = String>) -> Result<RunArgs, CliError> {
+    let mut day: Option<u32> = None;
+    let mut part: Option<u32> = None;
+    let mut input: Option<PathBuf> = None;
+    let mut format = Format::Text;
+    let mut all = false;
+    let mut bag: Option<String> = None;
+    let mut vocabulary: Option<String> = None;
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => day = Some(parse_number(&option, &value()?)?),
+            "-a" | "--all" => all = true,
+            "-p" | "--part" => part = Some(parse_number(&option, &value()?)?),
+            "-i" | "--input" => input = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => {
+                let value = value()?;
+                format = Format::from_name(&value).ok_or(CliError::UnknownFormat(value))?;
+            }
+            "-b" | "--bag" => bag = Some(value()?),
+            "--vocabulary" => vocabulary = Some(value()?),
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    if bag.is_some() && day.is_some_and(|day| day != 2) {
+        return Err(CliError::OnlyForDay("--bag".to_owned(), 2));
+    }
+    if vocabulary.is_some() && day.is_some_and(|day| day != 1) {
+        return Err(CliError::OnlyForDay("--vocabulary".to_owned(), 1));
+    }
+    if all {
+        if bag.is_some() {
+            return Err(CliError::Conflict("--all".to_owned(), "--bag".to_owned()));
+        }
+        if vocabulary.is_some() {
+            return Err(CliError::Conflict(
+                "--all".to_owned(),
+                "--vocabulary".to_owned(),
+            ));
+        }
+        if day.is_some() {
+            return Err(CliError::Conflict("--all".to_owned(), "--day".to_owned()));
+        }
+        if input.is_some() {
+            return Err(CliError::Conflict("--all".to_owned(), "--input".to_owned()));
+        }
+    } else if day.is_none() {
+        return Err(CliError::MissingDay);
+    }
+    Ok(RunArgs {
+        day,
+        part,
+        input,
+        format,
+        bag,
+        vocabulary,
+    })
+}
+
+fn parse_bench_args(args: &mut impl Iterator<Item = String>) -> Result<BenchArgs, CliError> {
+    let mut bench_args = BenchArgs {
+        day: None,
+        warmup: 1,
+        iterations: 10,
+        save: None,
+        baseline: None,
+        threshold: 10,
+        alloc: false,
+    };
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => bench_args.day = Some(parse_number(&option, &value()?)?),
+            "-w" | "--warmup" => bench_args.warmup = parse_number(&option, &value()?)?,
+            "-n" | "--iterations" => {
+                let value = value()?;
+                bench_args.iterations = parse_number(&option, &value)?;
+                if bench_args.iterations == 0 {
+                    return Err(CliError::InvalidNumber(option, value));
+                }
+            }
+            "-s" | "--save" => bench_args.save = Some(PathBuf::from(value()?)),
+            "-b" | "--baseline" => bench_args.baseline = Some(PathBuf::from(value()?)),
+            "-t" | "--threshold" => bench_args.threshold = parse_number(&option, &value()?)?,
+            "-a" | "--alloc" => bench_args.alloc = true,
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    // Allocation counts are not timings, they cannot be stored or compared as a baseline.
+    if bench_args.alloc {
+        if !cfg!(feature = "alloc-count") {
+            return Err(CliError::MissingFeature(
+                "--alloc".to_owned(),
+                "alloc-count".to_owned(),
+            ));
+        }
+        if bench_args.save.is_some() {
+            return Err(CliError::Conflict(
+                "--alloc".to_owned(),
+                "--save".to_owned(),
+            ));
+        }
+        if bench_args.baseline.is_some() {
+            return Err(CliError::Conflict(
+                "--alloc".to_owned(),
+                "--baseline".to_owned(),
+            ));
+        }
+    }
+    Ok(bench_args)
+}
+
+fn parse_verify_args(args: &mut impl Iterator<Item = String>) -> Result<VerifyArgs, CliError> {
+    let mut manifest: Option<PathBuf> = None;
+    let mut day: Option<u32> = None;
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-m" | "--manifest" => manifest = Some(PathBuf::from(value()?)),
+            "-d" | "--day" => day = Some(parse_number(&option, &value()?)?),
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    Ok(VerifyArgs { manifest, day })
+}
+
+fn parse_differential_args(
+    args: &mut impl Iterator<Item = String>,
+) -> Result<DifferentialArgs, CliError> {
+    let mut differential_args = DifferentialArgs {
+        day: None,
+        seeds: 20,
+        size: 12,
+    };
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => differential_args.day = Some(parse_number(&option, &value()?)?),
+            "-s" | "--seeds" => differential_args.seeds = parse_number(&option, &value()?)?,
+            "-n" | "--size" => differential_args.size = parse_number(&option, &value()?)?,
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    Ok(differential_args)
+}
+
+fn parse_generate_args(args: &mut impl Iterator<Item = String>) -> Result<GenerateArgs, CliError> {
+    let mut day: Option<u32> = None;
+    let mut seed: u64 = 0;
+    let mut size: Option<u32> = None;
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => day = Some(parse_number(&option, &value()?)?),
+            "-s" | "--seed" => {
+                let value = value()?;
+                seed = value
+                    .parse::<u64>()
+                    .map_err(|_| CliError::InvalidNumber(option, value))?;
+            }
+            "-n" | "--size" => size = Some(parse_number(&option, &value()?)?),
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    Ok(GenerateArgs {
+        day: day.ok_or(CliError::MissingDay)?,
+        seed,
+        size,
+    })
+}
+
+fn parse_render_args(args: &mut impl Iterator<Item = String>) -> Result<RenderArgs, CliError> {
+    let mut day: Option<u32> = None;
+    let mut input: Option<PathBuf> = None;
+    let mut steps: Option<usize> = None;
+    let mut output: Option<(PathBuf, ImageFormat)> = None;
+    let mut scale = 4;
+    let mut plain = false;
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => day = Some(parse_number(&option, &value()?)?),
+            "-i" | "--input" => input = Some(PathBuf::from(value()?)),
+            "-n" | "--steps" => steps = Some(parse_number(&option, &value()?)? as usize),
+            "-o" | "--output" => {
+                let path = PathBuf::from(value()?);
+                let format = ImageFormat::from_path(&path)
+                    .ok_or(CliError::UnknownImageFormat(path.clone()))?;
+                output = Some((path, format));
+            }
+            "-s" | "--scale" => {
+                let value = value()?;
+                scale = parse_number(&option, &value)? as usize;
+                if scale == 0 {
+                    return Err(CliError::InvalidNumber(option, value));
+                }
+            }
+            "--plain" => plain = true,
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    if plain && output.is_some() {
+        return Err(CliError::Conflict(
+            "--plain".to_owned(),
+            "--output".to_owned(),
+        ));
+    }
+    Ok(RenderArgs {
+        day: day.ok_or(CliError::MissingDay)?,
+        input,
+        steps,
+        output,
+        scale,
+        plain,
+    })
+}
+
+fn parse_frames_args(args: &mut impl Iterator<Item = String>) -> Result<FramesArgs, CliError> {
+    let mut day: Option<u32> = None;
+    let mut frames_args = FramesArgs {
+        day: 0,
+        input: None,
+        steps: None,
+        output: None,
+        format: None,
+        scale: 4,
+    };
+    while let Some(arg) = args.next() {
+        let (option, inline_value) = split_option(&arg);
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| CliError::MissingValue(option.clone()))
+        };
+        match option.as_str() {
+            "-d" | "--day" => day = Some(parse_number(&option, &value()?)?),
+            "-i" | "--input" => frames_args.input = Some(PathBuf::from(value()?)),
+            "-n" | "--steps" => {
+                frames_args.steps = Some(parse_number(&option, &value()?)? as usize)
+            }
+            "-o" | "--output" => frames_args.output = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => {
+                let value = value()?;
+                frames_args.format = match value.as_str() {
+                    "text" => None,
+                    _ => Some(
+                        ImageFormat::from_name(&value)
+                            .ok_or(CliError::UnknownFrameFormat(value))?,
+                    ),
+                };
+            }
+            "-s" | "--scale" => {
+                let value = value()?;
+                frames_args.scale = parse_number(&option, &value)? as usize;
+                if frames_args.scale == 0 {
+                    return Err(CliError::InvalidNumber(option, value));
+                }
+            }
+            _ => return Err(CliError::UnknownOption(arg)),
+        }
+    }
+    frames_args.day = day.ok_or(CliError::MissingDay)?;
+    Ok(frames_args)
+}
+
+// Options of any command that take no value, every other option takes the next argument unless
+// it is written as `--option=value`.
+const FLAGS: [&str; 6] = ["-a", "--all", "--alloc", "--plain", "-h", "--help"];
+
+/// Takes the global verbosity flags out of `args` and returns the log level they select. The
+/// value of an option is kept even when it looks like one of them, as in `--input -v`.
+pub fn split_verbosity(args: impl IntoIterator<Item = String>) -> (Level, Vec<String>) {
+    let mut verbose = 0;
+    let mut quiet = false;
+    let mut rest: Vec<String> = Vec::new();
+    let mut value_expected = false;
+    for arg in args {
+        if mem::take(&mut value_expected) {
+            rest.push(arg);
+            continue;
+        }
+        match arg.as_str() {
+            "-v" | "--verbose" => verbose += 1,
+            "-vv" => verbose += 2,
+            "-vvv" => verbose += 3,
+            "-q" | "--quiet" => quiet = true,
+            _ => {
+                let (option, inline_value) = split_option(&arg);
+                value_expected = option.starts_with('-')
+                    && option != "-"
+                    && inline_value.is_none()
+                    && !FLAGS.contains(&option.as_str());
+                rest.push(arg);
+            }
+        }
+    }
+    (Level::from_verbosity(verbose, quiet), rest)
+}
+
+pub fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Command, CliError> {
+    let mut args = args.into_iter();
+    let command = args.next().ok_or(CliError::MissingCommand)?;
+    match command.as_str() {
+        "run" => Ok(Command::Run(parse_run_args(&mut args)?)),
+        "bench" => Ok(Command::Bench(parse_bench_args(&mut args)?)),
+        "verify" => Ok(Command::Verify(parse_verify_args(&mut args)?)),
+        "differential" => Ok(Command::Differential(parse_differential_args(&mut args)?)),
+        "generate" => Ok(Command::Generate(parse_generate_args(&mut args)?)),
+        "render" => Ok(Command::Render(parse_render_args(&mut args)?)),
+        "frames" => Ok(Command::Frames(parse_frames_args(&mut args)?)),
+        "help" | "-h" | "--help" => Ok(Command::Help),
+        _ => Err(CliError::UnknownCommand(command)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn cli_run_with_all_options() {
+        let command = parse(&["run", "--day", "17", "--part", "2", "--input", "in.txt"]);
+        let Ok(Command::Run(args)) = command else {
+            panic!("expected run command");
+        };
+        assert_eq!(Some(17), args.day);
+        assert_eq!(Some(2), args.part);
+        assert_eq!(Some(PathBuf::from("in.txt")), args.input);
+        assert_eq!(Format::Text, args.format);
+    }
+
+    #[test]
+    fn cli_run_formats() {
+        let Ok(Command::Run(args)) = parse(&["run", "-d", "1", "--format=json"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!(Format::Json, args.format);
+        let Ok(Command::Run(args)) = parse(&["run", "-d", "1", "-f", "csv"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!(Format::Csv, args.format);
+        assert_eq!(
+            Some(CliError::UnknownFormat("xml".to_owned())),
+            parse(&["run", "-d", "1", "-f", "xml"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_run_with_inline_values() {
+        let Ok(Command::Run(args)) = parse(&["run", "--day=3", "-p", "1"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!(Some(3), args.day);
+        assert_eq!(Some(1), args.part);
+        assert_eq!(None, args.input);
+    }
+
+    #[test]
+    fn cli_run_all() {
+        let Ok(Command::Run(args)) = parse(&["run", "--all", "-p", "2"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!((None, Some(2)), (args.day, args.part));
+        assert_eq!(
+            Some(CliError::Conflict("--all".to_owned(), "--day".to_owned())),
+            parse(&["run", "-a", "-d", "3"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_run_bag() {
+        let Ok(Command::Run(args)) = parse(&["run", "-d", "2", "--bag", "3 red, 1 pink"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!(Some("3 red, 1 pink".to_owned()), args.bag);
+        assert_eq!(
+            Some(CliError::OnlyForDay("--bag".to_owned(), 2)),
+            parse(&["run", "-d", "5", "-b", "bag.txt"]).err()
+        );
+        assert_eq!(
+            Some(CliError::Conflict("--all".to_owned(), "--bag".to_owned())),
+            parse(&["run", "-a", "-b", "bag.txt"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_run_vocabulary() {
+        let Ok(Command::Run(args)) = parse(&["run", "-d", "1", "--vocabulary=german"]) else {
+            panic!("expected run command");
+        };
+        assert_eq!(Some("german".to_owned()), args.vocabulary);
+        assert_eq!(
+            Some(CliError::OnlyForDay("--vocabulary".to_owned(), 1)),
+            parse(&["run", "-d", "2", "--vocabulary", "words.txt"]).err()
+        );
+        assert_eq!(
+            Some(CliError::Conflict(
+                "--all".to_owned(),
+                "--vocabulary".to_owned()
+            )),
+            parse(&["run", "-a", "--vocabulary", "german"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_bench_defaults_and_options() {
+        let Ok(Command::Bench(args)) = parse(&["bench"]) else {
+            panic!("expected bench command");
+        };
+        assert_eq!(
+            (None, 1, 10, 10),
+            (args.day, args.warmup, args.iterations, args.threshold)
+        );
+        let command = parse(&["bench", "-d", "5", "--iterations=3", "-b", "old.json"]);
+        let Ok(Command::Bench(args)) = command else {
+            panic!("expected bench command");
+        };
+        assert_eq!((Some(5), 3), (args.day, args.iterations));
+        assert_eq!(Some(PathBuf::from("old.json")), args.baseline);
+        assert_eq!(
+            Some(CliError::InvalidNumber("-n".to_owned(), "0".to_owned())),
+            parse(&["bench", "-n", "0"]).err()
+        );
+        if cfg!(feature = "alloc-count") {
+            let Ok(Command::Bench(args)) = parse(&["bench", "--alloc", "-d", "12"]) else {
+                panic!("expected bench command");
+            };
+            assert!(args.alloc);
+            assert_eq!(
+                Some(CliError::Conflict(
+                    "--alloc".to_owned(),
+                    "--save".to_owned()
+                )),
+                parse(&["bench", "-a", "-s", "new.json"]).err()
+            );
+        } else {
+            assert_eq!(
+                Some(CliError::MissingFeature(
+                    "--alloc".to_owned(),
+                    "alloc-count".to_owned()
+                )),
+                parse(&["bench", "--alloc"]).err()
+            );
+        }
+    }
+
+    #[test]
+    fn cli_differential() {
+        let command = parse(&["differential", "-d", "14", "-n", "30"]);
+        let Ok(Command::Differential(args)) = command else {
+            panic!("expected differential command");
+        };
+        assert_eq!((Some(14), 20, 30), (args.day, args.seeds, args.size));
+    }
+
+    #[test]
+    fn cli_generate() {
+        let command = parse(&["generate", "-d", "22", "--seed=18446744073709551615"]);
+        let Ok(Command::Generate(args)) = command else {
+            panic!("expected generate command");
+        };
+        assert_eq!((22, u64::MAX, None), (args.day, args.seed, args.size));
+        assert_eq!(
+            Some(CliError::MissingDay),
+            parse(&["generate", "-n", "5"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_render() {
+        let Ok(Command::Render(args)) = parse(&["render", "-d", "10", "--plain"]) else {
+            panic!("expected render command");
+        };
+        assert_eq!((10, None, true), (args.day, args.input, args.plain));
+        assert_eq!((None, None, 4), (args.steps, args.output, args.scale));
+        assert_eq!(Some(CliError::MissingDay), parse(&["render"]).err());
+
+        let command = parse(&[
+            "render",
+            "-d",
+            "21",
+            "-n",
+            "500",
+            "-o",
+            "day21.png",
+            "-s",
+            "1",
+        ]);
+        let Ok(Command::Render(args)) = command else {
+            panic!("expected render command");
+        };
+        assert_eq!((Some(500), 1), (args.steps, args.scale));
+        assert_eq!(
+            Some((PathBuf::from("day21.png"), ImageFormat::Png)),
+            args.output
+        );
+        assert_eq!(
+            Some(CliError::UnknownImageFormat(PathBuf::from("day21.gif"))),
+            parse(&["render", "-d", "21", "--output=day21.gif"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_frames() {
+        let Ok(Command::Frames(args)) = parse(&["frames", "-d", "22"]) else {
+            panic!("expected frames command");
+        };
+        assert_eq!(
+            (22, None, None, 4),
+            (args.day, args.steps, args.format, args.scale)
+        );
+        let command = parse(&["frames", "-d", "14", "-n", "2", "-f", "png", "-o", "out"]);
+        let Ok(Command::Frames(args)) = command else {
+            panic!("expected frames command");
+        };
+        assert_eq!((Some(2), Some(ImageFormat::Png)), (args.steps, args.format));
+        assert_eq!(Some(PathBuf::from("out")), args.output);
+        assert_eq!(
+            Some(CliError::UnknownFrameFormat("gif".to_owned())),
+            parse(&["frames", "-d", "14", "--format=gif"]).err()
+        );
+    }
+
+    #[test]
+    fn cli_verbosity_anywhere() {
+        let args = ["-v", "run", "--day", "17", "--verbose"].map(|s| s.to_string());
+        let (level, rest) = split_verbosity(args);
+        assert_eq!(Level::Debug, level);
+        assert_eq!(vec!["run", "--day", "17"], rest);
+        let (level, _) = split_verbosity(["run", "-q"].map(|s| s.to_string()));
+        assert_eq!(Level::Error, level);
+        let (level, _) = split_verbosity(["run"].map(|s| s.to_string()));
+        assert_eq!(Level::Warn, level);
+        // Values of options are kept, also when they look like a verbosity flag.
+        let args = ["run", "-d", "1", "-i", "-v", "-a", "-q"].map(|s| s.to_string());
+        let (level, rest) = split_verbosity(args);
+        assert_eq!(Level::Error, level);
+        assert_eq!(vec!["run", "-d", "1", "-i", "-v", "-a"], rest);
+        let args = ["run", "--input=-", "-v", "-i", "-", "-vv"].map(|s| s.to_string());
+        let (level, rest) = split_verbosity(args);
+        assert_eq!(Level::Trace, level);
+        assert_eq!(vec!["run", "--input=-", "-i", "-"], rest);
+    }
+
+    #[test]
+    fn cli_run_errors() {
+        assert_eq!(Some(CliError::MissingCommand), parse(&[]).err());
+        assert_eq!(Some(CliError::MissingDay), parse(&["run"]).err());
+        assert_eq!(
+            Some(CliError::InvalidNumber("--day".to_owned(), "x".to_owned())),
+            parse(&["run", "--day", "x"]).err()
+        );
+        assert_eq!(
+            Some(CliError::MissingValue("--part".to_owned())),
+            parse(&["run", "--day", "1", "--part"]).err()
+        );
+        assert_eq!(
+            Some(CliError::UnknownOption("--fast".to_owned())),
+            parse(&["run", "--fast"]).err()
+        );
+    }
+}
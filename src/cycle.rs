@@ -0,0 +1,233 @@
+//! Cycle detection for simulations whose states eventually repeat.
+//!
+//! [`brent`] finds the cycle of a deterministic state transition and only keeps two states at a
+//! time. [`Detector`] finds the first repeat in a stream of hashable states, for simulations that
+//! are fed from outside. [`state_after`] and [`first_common_event`] extrapolate from a cycle.
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+use crate::math;
+
+/// States `prefix` and `prefix + period` are the first two equal states.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub prefix: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The first step that has the same state as step `n`.
+    pub fn equivalent(&self, n: usize) -> usize {
+        if n < self.prefix {
+            n
+        } else {
+            self.prefix + (n - self.prefix) % self.period
+        }
+    }
+}
+
+/// The cycle of the states reached from `start` by repeating `step`, with Brent's algorithm.
+///
+/// Does not return if the states never repeat.
+pub fn brent<T: Clone + PartialEq>(start: &T, mut step: impl FnMut(&T) -> T) -> Cycle {
+    // The tortoise waits at powers of two until the hare runs into it.
+    let mut power = 1;
+    let mut period = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(start);
+    while tortoise != hare {
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+    }
+
+    // With the hare a period ahead they meet at the first state of the cycle.
+    let mut tortoise = start.clone();
+    let mut hare = start.clone();
+    for _ in 0..period {
+        hare = step(&hare);
+    }
+    let mut prefix = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        prefix += 1;
+    }
+    Cycle { prefix, period }
+}
+
+/// The state after `n` steps from `start`, whole periods are skipped.
+pub fn state_after<T: Clone + PartialEq>(start: &T, mut step: impl FnMut(&T) -> T, n: usize) -> T {
+    let cycle = brent(start, &mut step);
+    let mut state = start.clone();
+    for _ in 0..cycle.equivalent(n) {
+        state = step(&state);
+    }
+    state
+}
+
+/// Remembers the states of a simulation step by step until one repeats.
+pub struct Detector<T> {
+    seen: HashMap<T, usize>,
+}
+
+impl<T: Hash + Eq> Default for Detector<T> {
+    fn default() -> Self {
+        Detector {
+            seen: HashMap::new(),
+        }
+    }
+}
+
+impl<T: Hash + Eq> Detector<T> {
+    pub fn new() -> Detector<T> {
+        Detector::default()
+    }
+
+    /// Adds the state of the next step, starting with step 0. Returns the cycle when the state was
+    /// seen before.
+    pub fn push(&mut self, state: T) -> Option<Cycle> {
+        let step = self.seen.len();
+        match self.seen.entry(state) {
+            Entry::Occupied(entry) => Some(Cycle {
+                prefix: *entry.get(),
+                period: step - entry.get(),
+            }),
+            Entry::Vacant(entry) => {
+                entry.insert(step);
+                None
+            }
+        }
+    }
+}
+
+/// The steps with an event in a simulation with a `cycle`. `events` are the steps before the end
+/// of the first period, later steps have an event if their [`Cycle::equivalent`] step has one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Schedule {
+    pub cycle: Cycle,
+    pub events: Vec<usize>,
+}
+
+impl Schedule {
+    pub fn has_event(&self, n: usize) -> bool {
+        self.events.contains(&self.cycle.equivalent(n))
+    }
+}
+
+/// The first step at which every schedule has an event, `None` if they never do at once or the
+/// periods combined do not fit into `u64`.
+pub fn first_common_event(schedules: &[Schedule]) -> Option<usize> {
+    // Until every schedule is in its cycle the steps are checked one by one.
+    let periodic_from = schedules
+        .iter()
+        .map(|schedule| schedule.cycle.prefix + schedule.cycle.period)
+        .max()
+        .unwrap_or(0);
+    if let Some(step) = (0..periodic_from).find(|n| schedules.iter().all(|s| s.has_event(*n))) {
+        return Some(step);
+    }
+
+    // Afterwards a step needs one of the periodic events of every schedule as its remainder.
+    let mut candidates: Vec<(u64, u64)> = vec![(0, 1)];
+    for schedule in schedules {
+        let period = schedule.cycle.period as u64;
+        let remainders: Vec<u64> = schedule
+            .events
+            .iter()
+            .filter(|event| **event >= schedule.cycle.prefix)
+            .map(|event| *event as u64)
+            .collect();
+        candidates = candidates
+            .iter()
+            .flat_map(|candidate| {
+                remainders
+                    .iter()
+                    .filter_map(|remainder| math::crt(&[*candidate, (*remainder, period)]))
+            })
+            .collect();
+    }
+    candidates
+        .iter()
+        .map(|candidate| math::first_from(*candidate, periodic_from as u64) as usize)
+        .min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num::Integer;
+
+    #[test]
+    fn cycle_brent_and_detector_agree() {
+        // 3 -> 10 -> 5 -> 16 -> 8 -> 4 -> 2 -> 1 -> 4
+        let collatz = |n: &u64| if n.is_even() { n / 2 } else { 3 * n + 1 };
+        let cycle = brent(&3, collatz);
+        assert_eq!(
+            Cycle {
+                prefix: 5,
+                period: 3
+            },
+            cycle
+        );
+
+        let mut detector = Detector::new();
+        let mut state = 3;
+        let found = loop {
+            if let Some(cycle) = detector.push(state) {
+                break cycle;
+            }
+            state = collatz(&state);
+        };
+        assert_eq!(cycle, found);
+        assert_eq!(1, state_after(&3, collatz, 1_000_000_000_000));
+        assert_eq!(16, state_after(&3, collatz, 3));
+    }
+
+    #[test]
+    fn cycle_without_prefix() {
+        let cycle = brent(&0, |n| (n + 1) % 7);
+        assert_eq!(
+            Cycle {
+                prefix: 0,
+                period: 7
+            },
+            cycle
+        );
+        assert_eq!(3, cycle.equivalent(703));
+    }
+
+    #[test]
+    fn cycle_first_common_event() {
+        let every = |prefix, period, events: &[usize]| Schedule {
+            cycle: Cycle { prefix, period },
+            events: events.to_vec(),
+        };
+        // Clean periods meet at their least common multiple.
+        let clean = [every(0, 4, &[0]), every(0, 6, &[0])];
+        assert_eq!(Some(0), first_common_event(&clean));
+        let clean = [every(1, 4, &[4]), every(1, 6, &[6])];
+        assert_eq!(Some(12), first_common_event(&clean));
+        // An event before the cycle, then remainders 1 mod 4 and 2 mod 3.
+        assert_eq!(
+            Some(2),
+            first_common_event(&[every(3, 4, &[2, 5]), every(0, 3, &[2])])
+        );
+        assert_eq!(
+            Some(5),
+            first_common_event(&[every(3, 4, &[5]), every(0, 3, &[2])])
+        );
+        // Even and odd steps never meet.
+        assert_eq!(
+            None,
+            first_common_event(&[every(0, 2, &[0]), every(0, 2, &[1])])
+        );
+    }
+}
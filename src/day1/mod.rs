@@ -1,89 +1,573 @@
-use std::fs;
-
-fn get_first_real_number(encoded_string: &String) -> (u32, usize) {
-    let encoded_chars: Vec<char> = encoded_string.chars().collect();
-    for i in 0..encoded_string.len() {
-        let char = encoded_chars[i];
-        if char.is_numeric() {
-            return (char.to_digit(10).unwrap(), i);
-        }
-    }
-    return (0, encoded_string.len() * 2);
-}
-
-fn get_first_string_number(
-    encoded_string: &String,
-    numbers_as_strings: &Vec<String>,
-) -> (usize, usize) {
-    let mut first_index = encoded_string.len() * 2;
-    let mut first_number = 0;
-    for i in 0..numbers_as_strings.len() {
-        let number_as_string = &numbers_as_strings[i];
-        if encoded_string.contains(number_as_string.as_str()) {
-            let found_index = encoded_string.find(number_as_string.as_str()).unwrap();
-            if found_index < first_index {
-                first_number = i + 1;
-                first_index = found_index;
+//! Day 1: Trebuchet?!.
+//!
+//! Calibration values from the first and last digit of every line, part two also reads
+//! digits that are spelled out. The spelled digits come from a [`Vocabulary`], English for the
+//! puzzle, other languages as presets or from a file. A [`Decoder`] finds the first and last digit
+//! in a single pass, [`solve_reader`] decodes a document while it is read. [`Decoder::tokens`]
+//! lists every digit of a line with its position, to see how a calibration value came about.
+
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, VecDeque},
+    fmt, fs,
+    io::{self, BufRead},
+    ops::Range,
+    path::{Path, PathBuf},
+    slice,
+};
+
+use crate::{
+    input::{self, ParseError},
+    solution::Solution,
+};
+
+/// Names of the vocabularies [`Vocabulary::preset`] knows.
+pub const PRESETS: [&str; 5] = ["english", "german", "french", "spanish", "roman"];
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum VocabularyError {
+    Unreadable(PathBuf),
+    Invalid(usize, String),
+    /// A word that stands for two different digits.
+    Ambiguous(String, u32, u32),
+    /// A word containing a digit, which already counts on its own.
+    DigitInWord(String),
+    /// A word for a number above 9, which is no digit of a calibration value.
+    NotADigit(String, u32),
+    /// The empty word, which would be found everywhere.
+    EmptyWord,
+}
+
+impl fmt::Display for VocabularyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VocabularyError::Unreadable(path) => {
+                write!(f, "cannot read vocabulary {}", path.display())
+            }
+            VocabularyError::Invalid(line, reason) => {
+                write!(f, "vocabulary line {line}: {reason}")
+            }
+            VocabularyError::Ambiguous(word, digit, other_digit) => {
+                write!(f, "'{word}' stands for both {digit} and {other_digit}")
             }
+            VocabularyError::DigitInWord(word) => {
+                write!(f, "'{word}' contains a digit")
+            }
+            VocabularyError::NotADigit(word, number) => {
+                write!(f, "'{word}' stands for {number}, expected a digit 0-9")
+            }
+            VocabularyError::EmptyWord => write!(f, "the empty word is not a digit"),
         }
     }
-    return (first_number, first_index);
 }
 
-pub fn solver() {
-    let input =
-        fs::read_to_string("./src/day1/input.txt").expect("Should have been able to read the file");
-    let encoded_calibration_values = input.split("\r\n");
-    let mut sum_part_one = 0;
-    let mut sum_part_two = 0;
+/// Words that stand for digits, matched case sensitively.
+///
+/// Words may overlap in a line, `eightwo` starts with eight and ends with two. When several words
+/// start at the same character the longest one is read, as is the longest of several words that
+/// end at the same character, so `VIII` is 8 read from either side.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Vocabulary {
+    words: Vec<(String, u32)>,
+}
 
-    for encoded_line in encoded_calibration_values {
-        let mut number_part_one: String = "".to_owned();
-        let mut number_part_two: String = "".to_owned();
+impl Vocabulary {
+    /// Fails on words that stand for two digits, for numbers above 9 or contain a digit, and on the
+    /// empty word.
+    pub fn new(words: Vec<(String, u32)>) -> Result<Vocabulary, VocabularyError> {
+        let mut digits: HashMap<&str, u32> = HashMap::new();
+        for (word, digit) in &words {
+            if word.is_empty() {
+                return Err(VocabularyError::EmptyWord);
+            }
+            if word.chars().any(|c| c.is_ascii_digit()) {
+                return Err(VocabularyError::DigitInWord(word.to_owned()));
+            }
+            if *digit > 9 {
+                return Err(VocabularyError::NotADigit(word.to_owned(), *digit));
+            }
+            match digits.insert(word, *digit) {
+                Some(other_digit) if other_digit != *digit => {
+                    return Err(VocabularyError::Ambiguous(
+                        word.to_owned(),
+                        other_digit,
+                        *digit,
+                    ))
+                }
+                _ => {}
+            }
+        }
+        Ok(Vocabulary { words })
+    }
+
+    // The words of 1 to 9 in order.
+    fn from_words(words: [&str; 9]) -> Vocabulary {
+        Vocabulary {
+            words: (1..)
+                .zip(words)
+                .map(|(digit, word)| (word.to_owned(), digit))
+                .collect(),
+        }
+    }
 
-        let encoded_string: String = encoded_line.to_string();
-        let numbers_as_strings: Vec<String> = vec![
+    pub fn english() -> Vocabulary {
+        Vocabulary::from_words([
             "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-        ]
+        ])
+    }
+
+    /// A preset of [`PRESETS`] by name.
+    pub fn preset(name: &str) -> Option<Vocabulary> {
+        let words = match name {
+            "english" => return Some(Vocabulary::english()),
+            "german" => [
+                "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+            ],
+            "french" => [
+                "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+            ],
+            "spanish" => [
+                "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+            ],
+            "roman" => ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"],
+            _ => return None,
+        };
+        Some(Vocabulary::from_words(words))
+    }
+
+    /// One `word digit` per line, blank lines and lines starting with `#` are skipped.
+    pub fn parse(text: &str) -> Result<Vocabulary, VocabularyError> {
+        let mut words: Vec<(String, u32)> = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let invalid = |reason: String| VocabularyError::Invalid(index + 1, reason);
+            let [word, digit] = fields[..] else {
+                return Err(invalid(format!("expected 'word digit', found '{line}'")));
+            };
+            let digit = digit
+                .parse::<u32>()
+                .ok()
+                .filter(|digit| *digit <= 9)
+                .ok_or_else(|| invalid(format!("expected a digit, found '{digit}'")))?;
+            words.push((word.to_owned(), digit));
+        }
+        Vocabulary::new(words)
+    }
+
+    /// The preset called `name`, otherwise the vocabulary in the file at `name`.
+    pub fn load(name: &str) -> Result<Vocabulary, VocabularyError> {
+        if let Some(vocabulary) = Vocabulary::preset(name) {
+            return Ok(vocabulary);
+        }
+        let path = Path::new(name);
+        let text =
+            fs::read_to_string(path).map_err(|_| VocabularyError::Unreadable(path.to_owned()))?;
+        Vocabulary::parse(&text)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenKind {
+    Numeric,
+    Spelled,
+}
+
+/// A digit found in a line, with its half-open span in bytes and in chars.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Token {
+    pub digit: u32,
+    pub kind: TokenKind,
+    pub bytes: Range<usize>,
+    pub chars: Range<usize>,
+}
+
+// A digit or word the decoder looks for, its length in bytes and chars.
+#[derive(Clone, Copy)]
+struct Pattern {
+    bytes: usize,
+    chars: usize,
+    digit: u32,
+    kind: TokenKind,
+}
+
+/// A multi-pattern automaton (Aho-Corasick) for the digits and the words of a [`Vocabulary`].
+///
+/// It reads a line byte by byte once and sees every word that ends at a byte, also when words
+/// overlap, so neither lines nor words have to be searched again or reversed.
+pub struct Decoder {
+    // The next state for every byte, failures already followed.
+    transitions: Vec<[u32; 256]>,
+    // Every pattern ending in a state, longest first.
+    outputs: Vec<Vec<Pattern>>,
+}
+
+// The first and last digit of a line read so far.
+#[derive(Default)]
+struct LineScan {
+    state: u32,
+    position: usize,
+    // start, length and digit of the word starting first
+    first: Option<(usize, usize, u32)>,
+    last: Option<u32>,
+}
+
+impl LineScan {
+    // 0 for lines without any digit.
+    fn value(&self) -> usize {
+        match (self.first, self.last) {
+            (Some((_, _, first)), Some(last)) => (first * 10 + last) as usize,
+            _ => 0,
+        }
+    }
+}
+
+impl Decoder {
+    pub fn new(vocabulary: &Vocabulary) -> Decoder {
+        let digits: Vec<(String, u32)> = (0..10).map(|digit| (digit.to_string(), digit)).collect();
+        let mut transitions: Vec<[u32; 256]> = vec![[0; 256]];
+        let mut outputs: Vec<Vec<Pattern>> = vec![vec![]];
+        let patterns = digits.iter().map(|word| (word, TokenKind::Numeric)).chain(
+            vocabulary
+                .words
+                .iter()
+                .map(|word| (word, TokenKind::Spelled)),
+        );
+        for ((word, digit), kind) in patterns {
+            let mut state = 0;
+            for byte in word.bytes() {
+                if transitions[state][byte as usize] == 0 {
+                    transitions[state][byte as usize] = transitions.len() as u32;
+                    transitions.push([0; 256]);
+                    outputs.push(vec![]);
+                }
+                state = transitions[state][byte as usize] as usize;
+            }
+            outputs[state].push(Pattern {
+                bytes: word.len(),
+                chars: word.chars().count(),
+                digit: *digit,
+                kind,
+            });
+        }
+
+        // Breadth first, the failure state of a state is always done before it.
+        let mut failures: Vec<usize> = vec![0; transitions.len()];
+        let mut queue: VecDeque<usize> = transitions[0]
+            .iter()
+            .filter(|next| **next != 0)
+            .map(|next| *next as usize)
+            .collect();
+        while let Some(state) = queue.pop_front() {
+            for byte in 0..256 {
+                let failure_next = transitions[failures[state]][byte];
+                let next = transitions[state][byte] as usize;
+                if next == 0 {
+                    transitions[state][byte] = failure_next;
+                    continue;
+                }
+                failures[next] = failure_next as usize;
+                let inherited = outputs[failure_next as usize].clone();
+                outputs[next].extend(inherited);
+                queue.push_back(next);
+            }
+        }
+        for words in &mut outputs {
+            words.sort_by_key(|pattern| Reverse(pattern.bytes));
+        }
+        Decoder {
+            transitions,
+            outputs,
+        }
+    }
+
+    fn feed(&self, line: &mut LineScan, byte: u8) {
+        line.state = self.transitions[line.state as usize][byte as usize];
+        line.position += 1;
+        let patterns = &self.outputs[line.state as usize];
+        for pattern in patterns {
+            let start = line.position - pattern.bytes;
+            let earlier = line.first.is_none_or(|(first_start, first_length, _)| {
+                start < first_start || (start == first_start && pattern.bytes > first_length)
+            });
+            if earlier {
+                line.first = Some((start, pattern.bytes, pattern.digit));
+            }
+        }
+        if let Some(pattern) = patterns.first() {
+            line.last = Some(pattern.digit);
+        }
+    }
+
+    /// Every digit and spelled digit of `line` ordered by where they start, longer ones first.
+    /// Overlapping and nested words are all listed.
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        let mut tokens: Vec<Token> = Vec::new();
+        let mut state = 0;
+        let mut chars = 0;
+        for (index, byte) in line.bytes().enumerate() {
+            // Words are whole chars, so they only end where a char ends.
+            if byte & 0xc0 != 0x80 {
+                chars += 1;
+            }
+            state = self.transitions[state][byte as usize] as usize;
+            tokens.extend(self.outputs[state].iter().map(|pattern| Token {
+                digit: pattern.digit,
+                kind: pattern.kind,
+                bytes: index + 1 - pattern.bytes..index + 1,
+                chars: chars - pattern.chars..chars,
+            }));
+        }
+        tokens.sort_by_key(|token| (token.bytes.start, Reverse(token.bytes.end)));
+        tokens
+    }
+
+    /// The tokens that give the first and the last digit of the calibration value of `line`, as
+    /// [`Decoder::calibration_value`] reads them.
+    pub fn calibration_tokens(&self, line: &str) -> Option<(Token, Token)> {
+        let tokens = self.tokens(line);
+        let last = tokens
+            .iter()
+            .max_by_key(|token| (token.bytes.end, token.bytes.len()))?;
+        Some((tokens[0].clone(), last.clone()))
+    }
+
+    /// The calibration value of one line, 0 if it has no digit.
+    pub fn calibration_value(&self, line: &[u8]) -> usize {
+        let mut scan = LineScan::default();
+        for byte in line {
+            self.feed(&mut scan, *byte);
+        }
+        scan.value()
+    }
+
+    /// The sum of the calibration values of every line in `reader`, in a single pass over its
+    /// buffer without collecting lines. Lines end with LF, CRLF or CR.
+    pub fn calibration_sum(&self, reader: impl BufRead) -> io::Result<usize> {
+        calibration_sums(slice::from_ref(self), reader).map(|sums| sums[0])
+    }
+}
+
+// The calibration sum of every decoder, all fed from the same pass over `reader`.
+fn calibration_sums(decoders: &[Decoder], mut reader: impl BufRead) -> io::Result<Vec<usize>> {
+    let mut sums: Vec<usize> = vec![0; decoders.len()];
+    let mut scans: Vec<LineScan> = decoders.iter().map(|_| LineScan::default()).collect();
+    loop {
+        let buffer = match reader.fill_buf() {
+            Ok([]) => break,
+            Ok(buffer) => buffer,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        };
+        for byte in buffer {
+            for ((decoder, scan), sum) in decoders.iter().zip(&mut scans).zip(&mut sums) {
+                if *byte == b'\n' || *byte == b'\r' {
+                    *sum += scan.value();
+                    *scan = LineScan::default();
+                } else {
+                    decoder.feed(scan, *byte);
+                }
+            }
+        }
+        let length = buffer.len();
+        reader.consume(length);
+    }
+    Ok(sums
         .into_iter()
-        .map(|s| s.to_owned())
+        .zip(scans)
+        .map(|(sum, scan)| sum + scan.value())
+        .collect())
+}
+
+/// The sum of the calibration values with the digits spelled in `vocabulary`.
+pub fn solve(input: &Vec<String>, vocabulary: &Vocabulary) -> usize {
+    let decoder = Decoder::new(vocabulary);
+    input
+        .iter()
+        .map(|encoded_string| decoder.calibration_value(encoded_string.as_bytes()))
+        .sum()
+}
+
+/// [`solve`] for a calibration document that is read as it is decoded.
+pub fn solve_reader(reader: impl BufRead, vocabulary: &Vocabulary) -> io::Result<usize> {
+    Decoder::new(vocabulary).calibration_sum(reader)
+}
+
+/// [`solve_reader`] for every one of `vocabularies`, the document is still read only once.
+pub fn solve_reader_each(
+    reader: impl BufRead,
+    vocabularies: &[&Vocabulary],
+) -> io::Result<Vec<usize>> {
+    let decoders: Vec<Decoder> = vocabularies
+        .iter()
+        .map(|vocabulary| Decoder::new(vocabulary))
         .collect();
+    calibration_sums(&decoders, reader)
+}
 
-        let (mut first_number, first_number_index) =
-            get_first_string_number(&encoded_string, &numbers_as_strings);
-        let (first_digit, first_digit_index) = get_first_real_number(&encoded_string);
+pub fn solve_part_one(input: &Vec<String>) -> usize {
+    solve(input, &Vocabulary::default())
+}
 
-        if first_digit_index < first_number_index {
-            first_number = usize::try_from(first_digit).unwrap();
-        }
+pub fn solve_part_two(input: &Vec<String>) -> usize {
+    solve(input, &Vocabulary::english())
+}
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    Ok(input::lines(input))
+}
 
-        number_part_one.push_str(first_digit.to_string().as_str());
-        number_part_two.push_str(first_number.to_string().as_str());
+pub fn get_input(file: &str) -> Result<Vec<String>, ParseError> {
+    parse(&input::read_file(file))
+}
 
-        let reversed_numbers_as_strings: Vec<String> = numbers_as_strings
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_calibration_value(encoded_string: &str, vocabulary: &Vocabulary) -> usize {
+        Decoder::new(vocabulary).calibration_value(encoded_string.as_bytes())
+    }
+
+    #[test]
+    fn day1_input_part_one() {
+        let input = get_input("./src/day1/input.txt").unwrap();
+        let sum_part_one = solve_part_one(&input);
+        assert_eq!(55090, sum_part_one);
+    }
+
+    #[test]
+    fn day1_input_part_two() {
+        let input = get_input("./src/day1/input.txt").unwrap();
+        let sum_part_two = solve_part_two(&input);
+        assert_eq!(54845, sum_part_two);
+    }
+
+    #[test]
+    fn day1_overlapping_words() {
+        let english = Vocabulary::english();
+        assert_eq!(82, get_calibration_value("eightwo", &english));
+        assert_eq!(23, get_calibration_value("xtwone3four8eighthree", &english));
+        assert_eq!(22, get_calibration_value("2", &Vocabulary::default()));
+        assert_eq!(0, get_calibration_value("abc", &english));
+        let german = Vocabulary::preset("german").unwrap();
+        assert_eq!(53, get_calibration_value("fünfzweidreiß", &german));
+        let roman = Vocabulary::preset("roman").unwrap();
+        assert_eq!(88, get_calibration_value("xVIIIx", &roman));
+        assert_eq!(49, get_calibration_value("IVIX", &roman));
+    }
+
+    #[test]
+    fn day1_streaming_decoder() {
+        let file = fs::File::open("./src/day1/input.txt").unwrap();
+        let english = Vocabulary::english();
+        assert_eq!(
+            54845,
+            solve_reader(io::BufReader::new(file), &english).unwrap()
+        );
+        // Words split across reads and every kind of line ending.
+        let text = "two1nine\r\neightwothree\rabcone2threexyz\nxtwone3four\n\n7pqrstsixteen";
+        let reader = io::BufReader::with_capacity(3, text.as_bytes());
+        assert_eq!(
+            29 + 83 + 13 + 24 + 76,
+            solve_reader(reader, &english).unwrap()
+        );
+        assert_eq!(0, solve_reader(io::empty(), &english).unwrap());
+        let reader = io::BufReader::with_capacity(3, text.as_bytes());
+        assert_eq!(
+            vec![11 + 22 + 33 + 77, 29 + 83 + 13 + 24 + 76],
+            solve_reader_each(reader, &[&Vocabulary::default(), &english]).unwrap()
+        );
+    }
+
+    #[test]
+    fn day1_token_spans() {
+        let german = Decoder::new(&Vocabulary::preset("german").unwrap());
+        let tokens = german.tokens("ßfünf7zweins");
+        let spans: Vec<(u32, TokenKind, Range<usize>, Range<usize>)> = tokens
             .into_iter()
-            .map(|s| s.chars().rev().collect::<String>())
+            .map(|token| (token.digit, token.kind, token.bytes, token.chars))
             .collect();
+        assert_eq!(
+            vec![
+                (5, TokenKind::Spelled, 2..7, 1..5),
+                (7, TokenKind::Numeric, 7..8, 5..6),
+                (2, TokenKind::Spelled, 8..12, 6..10),
+                (1, TokenKind::Spelled, 10..14, 8..12),
+            ],
+            spans
+        );
 
-        let (mut last_number, last_number_index) = get_first_string_number(
-            &encoded_string.chars().rev().collect::<String>(),
-            &reversed_numbers_as_strings,
+        let roman = Decoder::new(&Vocabulary::preset("roman").unwrap());
+        let (first, last) = roman.calibration_tokens("xVIIIx").unwrap();
+        assert_eq!(
+            (8, 1..5, 8, 1..5),
+            (first.digit, first.chars, last.digit, last.chars)
         );
-        let (last_digit, last_digit_index) =
-            get_first_real_number(&encoded_string.chars().rev().collect::<String>());
+        assert_eq!(10, roman.tokens("VIII").len());
+        assert_eq!(None, roman.calibration_tokens("xyz"));
 
-        if last_digit_index < last_number_index {
-            last_number = usize::try_from(last_digit).unwrap();
+        // The tokens agree with the streaming decoder on every line of the puzzle.
+        let english = Decoder::new(&Vocabulary::english());
+        for line in get_input("./src/day1/input.txt").unwrap() {
+            let (first, last) = english.calibration_tokens(&line).unwrap();
+            let value = (first.digit * 10 + last.digit) as usize;
+            assert_eq!(english.calibration_value(line.as_bytes()), value);
         }
-
-        number_part_one.push_str(last_digit.to_string().as_str());
-        number_part_two.push_str(last_number.to_string().as_str());
-
-        sum_part_one += number_part_one.parse::<i32>().unwrap();
-        sum_part_two += number_part_two.parse::<i32>().unwrap();
     }
 
-    println!("Day1:");
-    println!("Sum of calibration values part one:  {sum_part_one}");
-    println!("Sum of calibration values part two:  {sum_part_two}");
+    #[test]
+    fn day1_vocabulary_from_file() {
+        let vocabulary = Vocabulary::parse("# Dutch\neen 1\ntwee 2\n\ndrie 3\n").unwrap();
+        assert_eq!(13, get_calibration_value("eentweedrie", &vocabulary));
+        assert_eq!(
+            Err(VocabularyError::Ambiguous("een".to_owned(), 1, 2)),
+            Vocabulary::parse("een 1\neen 2")
+        );
+        assert_eq!(
+            Err(VocabularyError::DigitInWord("2nd".to_owned())),
+            Vocabulary::parse("2nd 2")
+        );
+        assert_eq!(
+            Err(VocabularyError::EmptyWord),
+            Vocabulary::new(vec![(String::new(), 1)])
+        );
+        assert_eq!(
+            Err(VocabularyError::NotADigit("ten".to_owned(), 10)),
+            Vocabulary::new(vec![("ten".to_owned(), 10)])
+        );
+        assert_eq!(
+            Err(VocabularyError::Invalid(
+                1,
+                "expected a digit, found 'x'".to_owned()
+            )),
+            Vocabulary::parse("een x")
+        );
+        assert_eq!(Ok(Vocabulary::english()), Vocabulary::load("english"));
+        assert_eq!(
+            Err(VocabularyError::Unreadable(PathBuf::from("klingon.txt"))),
+            Vocabulary::load("klingon.txt")
+        );
+    }
 }
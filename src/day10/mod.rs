@@ -1,48 +1,52 @@
-use std::fs;
+//! Day 10: Pipe Maze.
+//!
+//! Enclosed tiles are counted on a copy of the map that only keeps the loop.
 
-fn find_start(map: &Vec<Vec<char>>) -> (usize, usize) {
-    for row in 0..map.len() {
-        for col in 0..map[row].len() {
-            if map[row][col] == 'S' {
-                return (row, col);
-            }
-        }
-    }
-    panic!("No start found");
-}
+use crate::{
+    geometry::{Dir, Point},
+    grid::Grid,
+    input::{self, ParseError},
+    solution::Solution,
+};
 
-fn find_start_directions(
-    map: &Vec<Vec<char>>,
-    (row_start, col_start): &(usize, usize),
-) -> [(usize, usize); 2] {
-    let mut next_positions: Vec<(usize, usize)> = Vec::new();
-    if row_start + 1 < map.len() {
-        match map[row_start + 1][*col_start] {
-            '|' | 'L' | 'J' => next_positions.push((row_start + 1, *col_start)),
-            _ => (),
-        }
-    }
+fn find_start(map: &Grid<char>) -> Point {
+    Point::from(
+        map.position(|tile| *tile == 'S')
+            .expect("parse checks for the start"),
+    )
+}
 
-    if *row_start > 0 {
-        match map[row_start - 1][*col_start] {
-            '|' | '7' | 'F' => next_positions.push((row_start - 1, *col_start)),
-            _ => (),
-        }
+// The two directions a pipe connects, `None` for ground and the start.
+fn pipe_directions(pipe: char) -> Option<[Dir; 2]> {
+    match pipe {
+        '|' => Some([Dir::Up, Dir::Down]),
+        '-' => Some([Dir::Left, Dir::Right]),
+        'J' => Some([Dir::Up, Dir::Left]),
+        'L' => Some([Dir::Up, Dir::Right]),
+        '7' => Some([Dir::Down, Dir::Left]),
+        'F' => Some([Dir::Down, Dir::Right]),
+        _ => None,
     }
+}
 
-    if col_start + 1 < map[*row_start].len() {
-        match map[*row_start][col_start + 1] {
-            '-' | 'J' | '7' => next_positions.push((*row_start, col_start + 1)),
-            _ => (),
-        }
-    }
+/// The pipe that connects the directions `one` and `two`.
+pub fn pipe_between(one: Dir, two: Dir) -> Option<char> {
+    "|-LJ7F".chars().find(|pipe| {
+        let directions = pipe_directions(*pipe).unwrap();
+        directions.contains(&one) && directions.contains(&two)
+    })
+}
 
-    if *col_start > 0 {
-        match map[*row_start][col_start - 1] {
-            '-' | 'L' | 'F' => next_positions.push((*row_start, col_start - 1)),
-            _ => (),
-        }
-    }
+fn find_start_directions(map: &Grid<char>, start: &Point) -> [Point; 2] {
+    let next_positions: Vec<Point> = Dir::ALL
+        .into_iter()
+        .filter(|dir| {
+            map.try_get(*start + *dir)
+                .and_then(|pipe| pipe_directions(*pipe))
+                .is_some_and(|directions| directions.contains(&dir.reverse()))
+        })
+        .map(|dir| *start + dir)
+        .collect();
 
     if next_positions.len() == 2 {
         return [next_positions[0], next_positions[1]];
@@ -50,19 +54,13 @@ fn find_start_directions(
     panic!("Start connections more or less not found.")
 }
 
-fn find_connecting_positions(pipe: char, (row, col): (usize, usize)) -> [(usize, usize); 2] {
-    match pipe {
-        '|' => return [(row - 1, col), (row + 1, col)],
-        '-' => return [(row, col - 1), (row, col + 1)],
-        'J' => return [(row - 1, col), (row, col - 1)],
-        'L' => return [(row - 1, col), (row, col + 1)],
-        '7' => return [(row + 1, col), (row, col - 1)],
-        'F' => return [(row + 1, col), (row, col + 1)],
-        _ => panic!("Should have been a pipe"),
-    }
+fn find_connecting_positions(pipe: char, position: Point) -> [Point; 2] {
+    pipe_directions(pipe)
+        .expect("Should have been a pipe")
+        .map(|dir| position + dir)
 }
 
-fn find_loop(map: &Vec<Vec<char>>, start: &(usize, usize)) -> (Vec<(usize, usize)>, usize) {
+fn find_loop(map: &Grid<char>, start: &Point) -> (Vec<Point>, usize) {
     let mut last_positions = [vec![*start], vec![*start]];
     let mut current_positions = find_start_directions(map, start);
     let mut steps = 1;
@@ -73,15 +71,13 @@ fn find_loop(map: &Vec<Vec<char>>, start: &(usize, usize)) -> (Vec<(usize, usize
         }
         for i in 0..2 {
             let last_position = last_positions[i].last().unwrap();
-            let next_position = **find_connecting_positions(
-                map[current_positions[i].0][current_positions[i].1],
-                current_positions[i],
-            )
-            .iter()
-            .filter(|position| *position != last_position)
-            .collect::<Vec<&(usize, usize)>>()
-            .first()
-            .unwrap();
+            let next_position =
+                **find_connecting_positions(map[current_positions[i]], current_positions[i])
+                    .iter()
+                    .filter(|position| *position != last_position)
+                    .collect::<Vec<&Point>>()
+                    .first()
+                    .unwrap();
 
             last_positions[i].push(current_positions[i]);
             current_positions[i] = next_position;
@@ -96,179 +92,183 @@ fn find_loop(map: &Vec<Vec<char>>, start: &(usize, usize)) -> (Vec<(usize, usize
             .iter()
             .skip(1)
             .rev()
-            .map(|pos| *pos)
-            .collect::<Vec<(usize, usize)>>(),
+            .copied()
+            .collect::<Vec<Point>>(),
     );
     (pipe_loop, steps)
 }
 
-fn clean_map(input_map: &Vec<Vec<char>>, pipe_loop: &Vec<(usize, usize)>) -> Vec<Vec<char>> {
+fn clean_map(input_map: &Grid<char>, pipe_loop: &Vec<Point>) -> Grid<char> {
     let mut cleaned_map = input_map.clone();
-    for row in 0..cleaned_map.len() {
-        for col in 0..cleaned_map[row].len() {
-            if cleaned_map[row][col] == '.' {
-                continue;
-            }
-            if !pipe_loop.contains(&(row, col)) {
-                cleaned_map[row][col] = '.'
-            }
+    for position in input_map.positions() {
+        if cleaned_map[position] == '.' {
+            continue;
+        }
+        if !pipe_loop.contains(&Point::from(position)) {
+            cleaned_map[position] = '.'
         }
     }
 
-    let start_direction_one = (
-        pipe_loop[1].0 as i128 - pipe_loop[0].0 as i128,
-        pipe_loop[1].1 as i128 - pipe_loop[0].1 as i128,
-    );
-    let start_direction_two = (
-        pipe_loop.last().unwrap().0 as i128 - pipe_loop[0].0 as i128,
-        pipe_loop.last().unwrap().1 as i128 - pipe_loop[0].1 as i128,
-    );
-
-    let start_symbol: char;
-    match (start_direction_one, start_direction_two) {
-        ((_, 0), (_, 0)) => start_symbol = '|',
-        ((0, _), (0, _)) => start_symbol = '-',
-        ((0, 1), (-1, 0)) | ((-1, 0), (0, 1)) => start_symbol = 'L',
-        ((0, -1), (-1, 0)) | ((-1, 0), (0, -1)) => start_symbol = 'J',
-        ((0, -1), (1, 0)) | ((1, 0), (0, -1)) => start_symbol = '7',
-        ((0, 1), (1, 0)) | ((1, 0), (0, 1)) => start_symbol = 'F',
-        _ => panic!("Start block does not really connect"),
-    }
+    let start_symbol = Dir::from_offset(pipe_loop[1] - pipe_loop[0])
+        .zip(Dir::from_offset(*pipe_loop.last().unwrap() - pipe_loop[0]))
+        .and_then(|(one, two)| pipe_between(one, two))
+        .expect("Start block does not really connect");
 
-    cleaned_map[pipe_loop[0].0][pipe_loop[0].1] = start_symbol;
+    cleaned_map[pipe_loop[0]] = start_symbol;
     cleaned_map
 }
 
-fn count_enclosed_spaces(input_map: &Vec<Vec<char>>) -> usize {
+/// Number of [`enclosed_spaces`].
+pub fn count_enclosed_spaces(input_map: &Grid<char>) -> usize {
+    enclosed_spaces(input_map).len()
+}
+
+/// Tiles inside the loop of pipes on `input_map`, which may only contain the loop and `.` tiles.
+pub fn enclosed_spaces(input_map: &Grid<char>) -> Vec<(usize, usize)> {
     let mut map = input_map.clone();
-    for row in 0..map.len() {
+    for row in 0..map.height() {
         let mut is_inside = false;
         let mut last_wall = ' ';
-        for col in 0..map[row].len() {
-            if map[row][col] == '0' || map[row][col] == '.' {
+        for col in 0..map.width() {
+            if map[(row, col)] == '0' || map[(row, col)] == '.' {
                 if !is_inside {
-                    map[row][col] = '0';
+                    map[(row, col)] = '0';
                 }
                 continue;
             }
-            match (last_wall, map[row][col]) {
+            match (last_wall, map[(row, col)]) {
                 (_, '|') => is_inside = !is_inside,
-                (_, 'L') | (_, 'F') => last_wall = map[row][col],
+                (_, 'L') | (_, 'F') => last_wall = map[(row, col)],
                 ('L', '7') | ('F', 'J') => {
                     is_inside = !is_inside;
                     last_wall = ' '
                 }
                 ('L', 'J') | ('F', '7') => last_wall = ' ',
                 (_, '-') => (),
-                (_, _) => panic!("weird layout {} {}", last_wall, map[row][col]),
+                (_, _) => panic!("weird layout {} {}", last_wall, map[(row, col)]),
             }
         }
-        // println!("{:?}", map[row]);
+        // println!("{:?}", map.row(row));
     }
 
     map.iter()
-        .map(|row| {
-            row.iter()
-                .filter(|tile| **tile == '.')
-                .collect::<Vec<&char>>()
-                .len()
-        })
-        .sum()
+        .filter(|(_, tile)| **tile == '.')
+        .map(|(position, _)| position)
+        .collect()
 }
 
-fn solve_part_one(map: &Vec<Vec<char>>) -> usize {
+/// The tiles of the loop through the start tile, in the order they are connected.
+pub fn find_pipe_loop(map: &Grid<char>) -> Vec<Point> {
     let start = find_start(map);
-    find_loop(map, &start).1
+    find_loop(map, &start).0
 }
 
-fn solve_part_two(map: &Vec<Vec<char>>) -> usize {
+/// The tiles enclosed by the loop through the start tile.
+pub fn find_enclosed_tiles(map: &Grid<char>) -> Vec<(usize, usize)> {
+    enclosed_spaces(&clean_map(map, &find_pipe_loop(map)))
+}
+
+pub fn solve_part_one(map: &Grid<char>) -> usize {
     let start = find_start(map);
-    let pipe_loop = find_loop(map, &start).0;
-    let cleaned_map = clean_map(map, &pipe_loop);
-    count_enclosed_spaces(&cleaned_map)
+    find_loop(map, &start).1
 }
 
-fn get_input(file: &str) -> Vec<Vec<char>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let chars: Vec<Vec<char>> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned().chars().collect::<Vec<char>>())
-        .collect();
-    return chars;
+pub fn solve_part_two(map: &Grid<char>) -> usize {
+    find_enclosed_tiles(map).len()
+}
+
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    input::expect_single(Day10::DAY, input, 'S', "start 'S'")?;
+    input::parse_grid(Day10::DAY, input, "|-LJ7F.S")
 }
 
-pub fn solver() {
-    let input = get_input("./src/day10/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Grid<char>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day10_example_input_part_one() {
-        let input = get_input("./src/day10/example_input.txt");
+        let input = get_input("./src/day10/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(4, sum_part_one);
     }
 
     #[test]
     fn day10_example_input_two_part_one() {
-        let input = get_input("./src/day10/example_input_two.txt");
+        let input = get_input("./src/day10/example_input_two.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(8, sum_part_one);
     }
 
     #[test]
     fn day10_input_part_one() {
-        let input = get_input("./src/day10/input.txt");
+        let input = get_input("./src/day10/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(6828, sum_part_one);
     }
 
     #[test]
     fn day10_example_input_part_two() {
-        let input = get_input("./src/day10/example_input_part_two.txt");
+        let input = get_input("./src/day10/example_input_part_two.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(4, sum_part_two);
     }
 
     #[test]
     fn day10_example_input_two_part_two() {
-        let input = get_input("./src/day10/example_input_two_part_two.txt");
+        let input = get_input("./src/day10/example_input_two_part_two.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(8, sum_part_two);
     }
 
     #[test]
     fn day10_example_input_three_part_two() {
-        let input = get_input("./src/day10/example_input_three_part_two.txt");
+        let input = get_input("./src/day10/example_input_three_part_two.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(10, sum_part_two);
     }
 
     #[test]
     fn day10_input_part_two() {
-        let input = get_input("./src/day10/input.txt");
+        let input = get_input("./src/day10/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(459, sum_part_two);
     }
 
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day10/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day10/input.txt");
-        b.iter(|| solve_part_two(&input))
+    #[test]
+    fn day10_parse_needs_a_single_start() {
+        assert_eq!(
+            "day 10, line 2, column 4: expected a start 'S', found end of line",
+            parse("...\n...").unwrap_err().to_string()
+        );
+        assert_eq!(
+            (1, 3),
+            parse(".SS\n...")
+                .map_err(|e| (e.line, e.column))
+                .unwrap_err()
+        );
     }
 }
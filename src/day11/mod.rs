@@ -1,45 +1,40 @@
+//! Day 11: Cosmic Expansion.
+//!
+//! [`solve`] takes the expansion factor of empty rows and columns, 2 for part one and
+//! 1 000 000 for part two.
+
 use std::{
     cmp::{max, min},
-    fs,
+    iter,
 };
 
 use num::abs;
 
-fn find_empty_space(universe: &Vec<Vec<char>>) -> (Vec<usize>, Vec<usize>) {
-    let mut empty_rows: Vec<usize> = Vec::new();
-    let mut empty_cols: Vec<usize> = Vec::new();
-    for row in 0..universe.len() {
-        if !universe[row].contains(&'#') {
-            empty_rows.push(row);
-        }
-    }
+use crate::{
+    grid::Grid,
+    input::{self, ParseError},
+    solution::Solution,
+};
 
-    for col in 0..universe[0].len() {
-        let mut column_contains_universe = false;
-        for row in 0..universe.len() {
-            if universe[row][col] == '#' {
-                column_contains_universe = true;
-                break;
-            }
-        }
-        if column_contains_universe {
-            continue;
-        }
-        empty_cols.push(col);
-    }
+fn find_empty_space(universe: &Grid<char>) -> (Vec<usize>, Vec<usize>) {
+    let empty_rows: Vec<usize> = universe
+        .rows()
+        .enumerate()
+        .filter(|(_, row)| !row.contains(&'#'))
+        .map(|(row, _)| row)
+        .collect();
+    let empty_cols: Vec<usize> = (0..universe.width())
+        .filter(|&col| !universe.column(col).any(|symbol| *symbol == '#'))
+        .collect();
     (empty_rows, empty_cols)
 }
 
-fn find_galaxies(universe: &Vec<Vec<char>>) -> Vec<(i128, i128)> {
-    let mut galaxies: Vec<(i128, i128)> = Vec::new();
-    for row in 0..universe.len() {
-        universe[row]
-            .iter()
-            .enumerate()
-            .filter(|(_, symbol)| **symbol == '#')
-            .for_each(|(col, _)| galaxies.push((row as i128, col as i128)));
-    }
-    galaxies
+fn find_galaxies(universe: &Grid<char>) -> Vec<(i128, i128)> {
+    universe
+        .iter()
+        .filter(|(_, symbol)| **symbol == '#')
+        .map(|((row, col), _)| (row as i128, col as i128))
+        .collect()
 }
 
 fn get_distance_between_galaxies(
@@ -77,8 +72,30 @@ fn get_distance_between_galaxies(
         as usize
 }
 
-fn solve(universe: &Vec<Vec<char>>, factor: i128) -> usize {
-    let galaxies = find_galaxies(&universe);
+/// The universe with every empty row and column repeated `factor` times.
+pub fn expand(universe: &Grid<char>, factor: usize) -> Grid<char> {
+    let (empty_rows, empty_cols) = find_empty_space(universe);
+    let mut rows: Vec<Vec<char>> = Vec::new();
+    for (row_index, row) in universe.rows().enumerate() {
+        let mut expanded_row: Vec<char> = Vec::new();
+        for (col, symbol) in row.iter().enumerate() {
+            let copies = if empty_cols.contains(&col) { factor } else { 1 };
+            expanded_row.extend(iter::repeat_n(*symbol, copies));
+        }
+        let copies = if empty_rows.contains(&row_index) {
+            factor
+        } else {
+            1
+        };
+        rows.extend(iter::repeat_n(expanded_row, copies));
+    }
+    Grid::from_rows(rows)
+}
+
+/// Sum of the distances between all pairs of galaxies, every empty row and column counts
+/// `factor` times.
+pub fn solve(universe: &Grid<char>, factor: i128) -> usize {
+    let galaxies = find_galaxies(universe);
     let (empty_rows, empty_cols) = find_empty_space(universe);
     let mut combined_distance = 0;
     for i in 0..galaxies.len() {
@@ -95,73 +112,77 @@ fn solve(universe: &Vec<Vec<char>>, factor: i128) -> usize {
     combined_distance
 }
 
-fn get_input(file: &str) -> Vec<Vec<char>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let symbols: Vec<Vec<char>> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned().chars().collect::<Vec<char>>())
-        .collect();
-    return symbols;
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    input::parse_grid(Day11::DAY, input, ".#")
 }
 
-pub fn solver() {
-    let input = get_input("./src/day11/input.txt");
-    let sum_part_one = solve(&input, 2);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve(&input, 1000000);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Grid<char>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve(input, 2).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve(input, 1000000).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day11_example_input_part_one() {
-        let input = get_input("./src/day11/example_input.txt");
+        let input = get_input("./src/day11/example_input.txt").unwrap();
         let sum_part_one = solve(&input, 2);
         assert_eq!(374, sum_part_one);
     }
 
     #[test]
     fn day11_input_part_one() {
-        let input = get_input("./src/day11/input.txt");
+        let input = get_input("./src/day11/input.txt").unwrap();
         let sum_part_one = solve(&input, 2);
         assert_eq!(9723824, sum_part_one);
     }
 
+    #[test]
+    fn day11_expanded_example_input() {
+        let input = get_input("./src/day11/example_input.txt").unwrap();
+        let expanded = expand(&input, 2);
+        assert_eq!((13, 12), (expanded.width(), expanded.height()));
+        assert_eq!(374, solve(&expanded, 1));
+    }
+
     #[test]
     fn day11_example_input_part_two_10() {
-        let input = get_input("./src/day11/example_input.txt");
+        let input = get_input("./src/day11/example_input.txt").unwrap();
         let sum_part_two = solve(&input, 10);
         assert_eq!(1030, sum_part_two);
     }
 
     #[test]
     fn day11_example_input_part_two_100() {
-        let input = get_input("./src/day11/example_input.txt");
+        let input = get_input("./src/day11/example_input.txt").unwrap();
         let sum_part_two = solve(&input, 100);
         assert_eq!(8410, sum_part_two);
     }
 
     #[test]
     fn day11_input_part_two() {
-        let input = get_input("./src/day11/input.txt");
+        let input = get_input("./src/day11/input.txt").unwrap();
         let sum_part_two = solve(&input, 1000000);
         assert_eq!(731244261352, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day11/input.txt");
-        b.iter(|| solve(&input, 2))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day11/input.txt");
-        b.iter(|| solve(&input, 1000000))
-    }
 }
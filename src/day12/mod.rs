@@ -1,13 +1,22 @@
+//! Day 12: Hot Springs.
+//!
+//! Part two unfolds every [`Record`] five times before counting the arrangements.
+
 use rayon::prelude::*;
-use std::{
-    collections::HashMap,
-    fs,
+use std::collections::HashMap;
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
 };
 
+/// A row of springs with `?` for unknown ones and the sizes of the damaged groups.
+pub type Record = (Vec<char>, Vec<usize>);
+
 fn get_num_valid_arrangements(
     springs: &[char],
     groups: &[usize],
-    cache: &mut HashMap<(Vec<char>, Vec<usize>), usize>,
+    cache: &mut HashMap<Record, usize>,
     possible_failures: usize,
     necessary_failures: usize,
 ) -> usize {
@@ -23,7 +32,7 @@ fn get_num_valid_arrangements(
     }
 
     if groups.is_empty() {
-        if springs.iter().any(|spring| *spring == '#') {
+        if springs.contains(&'#') {
             return 0;
         }
         return 1;
@@ -41,7 +50,7 @@ fn get_num_valid_arrangements(
         '.' => {
             let result = get_num_valid_arrangements(
                 &springs[1..],
-                &groups,
+                groups,
                 cache,
                 possible_failures,
                 necessary_failures,
@@ -52,7 +61,7 @@ fn get_num_valid_arrangements(
         '?' => {
             let result_one = get_num_valid_arrangements(
                 &springs[1..],
-                &groups,
+                groups,
                 cache,
                 possible_failures - 1,
                 necessary_failures,
@@ -61,7 +70,7 @@ fn get_num_valid_arrangements(
 
             let result_two = get_num_valid_arrangements(
                 &[['#'].as_slice(), &springs[1..]].concat(),
-                &groups,
+                groups,
                 cache,
                 possible_failures,
                 necessary_failures,
@@ -77,8 +86,7 @@ fn get_num_valid_arrangements(
             if groups.is_empty() {
                 return 0;
             }
-            if springs.len() < groups[0] || springs[..groups[0]].iter().any(|spring| *spring == '.')
-            {
+            if springs.len() < groups[0] || springs[..groups[0]].contains(&'.') {
                 return 0;
             }
 
@@ -90,15 +98,18 @@ fn get_num_valid_arrangements(
                 if springs[groups[0]] == '?' {
                     new_possible_failures -= 1;
                 }
-                let result =get_num_valid_arrangements(
+                let result = get_num_valid_arrangements(
                     &springs[(groups[0] + 1)..],
                     &groups[1..],
                     cache,
                     new_possible_failures,
                     necessary_failures - groups[0],
                 );
-                cache.insert((springs[(groups[0] + 1)..].to_vec(), groups[1..].to_vec()), result);
-                return result
+                cache.insert(
+                    (springs[(groups[0] + 1)..].to_vec(), groups[1..].to_vec()),
+                    result,
+                );
+                return result;
             }
             let result = get_num_valid_arrangements(
                 &springs[groups[0]..],
@@ -107,29 +118,26 @@ fn get_num_valid_arrangements(
                 0,
                 necessary_failures - groups[0],
             );
-            cache.insert((springs[groups[0]..].to_vec(), groups[1..].to_vec()), result);
+            cache.insert(
+                (springs[groups[0]..].to_vec(), groups[1..].to_vec()),
+                result,
+            );
             return result;
         }
-        _ => panic!("Illegal symbol"),
+        _ => unreachable!("springs are checked while parsing"),
     }
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
+pub fn solve_part_one(input: &Vec<Record>) -> usize {
     input
         .iter()
-        .map(|line| {
-            let split: Vec<String> = line.split_whitespace().map(|s| s.to_owned()).collect();
-            let springs: Vec<char> = split[0].chars().collect();
-            let groups: Vec<usize> = split[1]
-                .split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect();
+        .map(|(springs, groups)| {
             let possible_failures = springs.iter().filter(|spring| **spring != '.').count();
             let necessary_failures: usize = groups.iter().sum::<usize>();
             let mut cache = HashMap::new();
             get_num_valid_arrangements(
-                &springs,
-                &groups,
+                springs,
+                groups,
                 &mut cache,
                 possible_failures,
                 necessary_failures,
@@ -138,17 +146,10 @@ fn solve_part_one(input: &Vec<String>) -> usize {
         .sum()
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
+pub fn solve_part_two(input: &Vec<Record>) -> usize {
     input
         .par_iter()
-        .map(|line| {
-            let split: Vec<String> = line.split_whitespace().map(|s| s.to_owned()).collect();
-            let springs: Vec<char> = split[0].chars().collect();
-            let groups: Vec<usize> = split[1]
-                .split(',')
-                .map(|s| s.parse::<usize>().unwrap())
-                .collect();
-
+        .map(|(springs, groups)| {
             let mut adapted_springs = springs.to_vec();
             adapted_springs.push('?');
             adapted_springs.extend(springs.to_vec());
@@ -170,78 +171,89 @@ fn solve_part_two(input: &Vec<String>) -> usize {
                 .count();
             let necessary_failures: usize = adapted_groups.iter().sum::<usize>();
             let mut cache = HashMap::new();
-            let num_valid_arrangements = get_num_valid_arrangements(
+            get_num_valid_arrangements(
                 &adapted_springs,
                 &adapted_groups,
                 &mut cache,
                 possible_failures,
                 necessary_failures,
-            );
-            num_valid_arrangements
+            )
         })
         .sum()
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let input: Vec<String> = input
-        .split("\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return input;
+// springs, groups of damaged springs
+fn parse_record(line: Line) -> Result<Record, ParseError> {
+    let mut split = line.text().split_whitespace();
+    let springs = line.next(&mut split, "springs")?;
+    let springs = input::parse_row(springs, |spring, _| match spring {
+        "." | "#" | "?" => Ok(spring.chars().next().unwrap()),
+        _ => Err(line.error(spring, "a spring '.', '#' or '?'")),
+    })?;
+    let groups = line
+        .next(&mut split, "group sizes")?
+        .split(',')
+        .map(|s| line.parse::<usize>(s, "a group size"))
+        .collect::<Result<Vec<usize>, ParseError>>()?;
+    Ok((springs, groups))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day12/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn parse(input: &str) -> Result<Vec<Record>, ParseError> {
+    input::parse_lines(Day12::DAY, input, parse_record)
+}
+
+pub fn get_input(file: &str) -> Result<Vec<Record>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    type Input = Vec<Record>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day12_example_input_part_one() {
-        let input = get_input("./src/day12/example_input.txt");
+        let input = get_input("./src/day12/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(21, sum_part_one);
     }
 
     #[test]
     fn day12_input_part_one() {
-        let input = get_input("./src/day12/input.txt");
+        let input = get_input("./src/day12/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(7460, sum_part_one);
     }
 
     #[test]
     fn day12_example_input_part_two() {
-        let input = get_input("./src/day12/example_input.txt");
+        let input = get_input("./src/day12/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(525152, sum_part_two);
     }
 
     #[test]
     fn day12_input_part_two() {
-        let input = get_input("./src/day12/input.txt");
+        let input = get_input("./src/day12/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(6720660274964, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day12/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day12/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
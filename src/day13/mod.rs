@@ -1,15 +1,23 @@
-use std::fs;
-
-fn get_row_reflection_value(mirror: &Vec<Vec<char>>) -> usize {
-    for row in 1..mirror.len() {
+//! Day 13: Point of Incidence.
+//!
+//! Part two looks for the reflection that needs exactly one smudge to be fixed.
+
+use crate::{
+    grid::Grid,
+    input::{self, ParseError},
+    solution::Solution,
+};
+
+fn get_row_reflection_value(mirror: &Grid<char>) -> usize {
+    for row in 1..mirror.height() {
         let mut found_mirror = true;
-        if mirror[row] == mirror[row - 1] {
+        if mirror.row(row) == mirror.row(row - 1) {
             let mut reflection_row = (row as i128 - 1, row as i128);
             loop {
-                if reflection_row.0 < 0 || reflection_row.1 >= mirror.len() as i128 {
+                if reflection_row.0 < 0 || reflection_row.1 >= mirror.height() as i128 {
                     break;
                 }
-                if mirror[reflection_row.0 as usize] != mirror[reflection_row.1 as usize] {
+                if mirror.row(reflection_row.0 as usize) != mirror.row(reflection_row.1 as usize) {
                     found_mirror = false;
                     break;
                 }
@@ -24,20 +32,7 @@ fn get_row_reflection_value(mirror: &Vec<Vec<char>>) -> usize {
     return 0;
 }
 
-fn flip_to_col(mirror: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut col_based_mirror: Vec<Vec<char>> = vec![vec![]; mirror[0].len()];
-    for row in mirror {
-        for char_index in 0..row.len() {
-            col_based_mirror[char_index].push(row[char_index]);
-        }
-    }
-    col_based_mirror
-}
-
-fn count_differences(
-    reflection_one: &Vec<char>,
-    reflection_two: &Vec<char>,
-) -> (usize, Vec<usize>) {
+fn count_differences(reflection_one: &[char], reflection_two: &[char]) -> (usize, Vec<usize>) {
     if reflection_one == reflection_two {
         return (0, vec![]);
     }
@@ -56,20 +51,20 @@ fn count_differences(
         .unwrap()
 }
 
-fn find_reflection_row_with_smudged_mirror(mirror: &Vec<Vec<char>>) -> Option<usize> {
-    for row in 1..mirror.len() {
+fn find_reflection_row_with_smudged_mirror(mirror: &Grid<char>) -> Option<usize> {
+    for row in 1..mirror.height() {
         let mut differences = 0;
-        let diff = count_differences(&mirror[row], &mirror[row - 1]).0;
+        let diff = count_differences(mirror.row(row), mirror.row(row - 1)).0;
 
         if diff <= 1 {
             let mut reflection_row = (row as i128 - 1, row as i128);
             loop {
-                if reflection_row.0 < 0 || reflection_row.1 >= mirror.len() as i128 {
+                if reflection_row.0 < 0 || reflection_row.1 >= mirror.height() as i128 {
                     break;
                 }
                 differences += count_differences(
-                    &mirror[reflection_row.0 as usize],
-                    &mirror[reflection_row.1 as usize],
+                    mirror.row(reflection_row.0 as usize),
+                    mirror.row(reflection_row.1 as usize),
                 )
                 .0;
 
@@ -87,97 +82,89 @@ fn find_reflection_row_with_smudged_mirror(mirror: &Vec<Vec<char>>) -> Option<us
     None
 }
 
-fn solve_part_one(mirrors: &Vec<Vec<Vec<char>>>) -> usize {
+pub fn solve_part_one(mirrors: &Vec<Grid<char>>) -> usize {
     mirrors
         .iter()
         .map(|mirror| {
             let row_value = get_row_reflection_value(mirror);
-            let col_value = get_row_reflection_value(&flip_to_col(mirror));
+            let col_value = get_row_reflection_value(&mirror.transpose());
             row_value * 100 + col_value
         })
         .sum()
 }
 
-fn solve_part_two(mirrors: &Vec<Vec<Vec<char>>>) -> usize {
+pub fn solve_part_two(mirrors: &Vec<Grid<char>>) -> usize {
     mirrors
         .iter()
         .map(|mirror| {
             let smudge_row_mirror = find_reflection_row_with_smudged_mirror(mirror);
-            let smudge_col_mirror = find_reflection_row_with_smudged_mirror(&flip_to_col(mirror));
-            if smudge_row_mirror.is_some() {
-                return smudge_row_mirror.unwrap() * 100;
-            } 
+            let smudge_col_mirror = find_reflection_row_with_smudged_mirror(&mirror.transpose());
+            if let Some(smudge_row_mirror) = smudge_row_mirror {
+                return smudge_row_mirror * 100;
+            }
             smudge_col_mirror.unwrap()
         })
         .sum()
 }
 
-fn get_input(file: &str) -> Vec<Vec<Vec<char>>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let mirrors: Vec<Vec<Vec<char>>> = input
-        .split("\n\n")
-        .into_iter()
-        .map(|s| {
-            s.to_owned()
-                .lines()
-                .into_iter()
-                .map(|s| s.to_owned().chars().collect::<Vec<char>>())
-                .collect::<Vec<Vec<char>>>()
-        })
-        .collect();
-    return mirrors;
+pub fn parse(input: &str) -> Result<Vec<Grid<char>>, ParseError> {
+    input::parse_groups(Day13::DAY, input, |mirror| {
+        input::parse_grid_lines(mirror, ".#")
+    })
 }
 
-pub fn solver() {
-    let input = get_input("./src/day13/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Vec<Grid<char>>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+    type Input = Vec<Grid<char>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day13_example_input_part_one() {
-        let input = get_input("./src/day13/example_input.txt");
+        let input = get_input("./src/day13/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(405, sum_part_one);
     }
 
     #[test]
     fn day13_input_part_one() {
-        let input = get_input("./src/day13/input.txt");
+        let input = get_input("./src/day13/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(29846, sum_part_one);
     }
 
     #[test]
     fn day13_example_input_part_two() {
-        let input = get_input("./src/day13/example_input.txt");
+        let input = get_input("./src/day13/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(400, sum_part_two);
     }
 
     #[test]
     fn day13_input_part_two() {
-        let input = get_input("./src/day13/input.txt");
+        let input = get_input("./src/day13/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(25401, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day13/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day13/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
@@ -1,33 +1,26 @@
-use std::{collections::HashMap, fs};
-
-fn flip_to_col(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut col_based_platform: Vec<Vec<char>> = vec![vec![]; platform[0].len()];
-    for row in platform {
-        for char_index in 0..row.len() {
-            col_based_platform[char_index].push(row[char_index]);
-        }
-    }
-    col_based_platform
-}
-
-fn reverse_platform(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut reversed_platform: Vec<Vec<char>> = platform.to_vec();
-    for row in reversed_platform.iter_mut() {
-        row.reverse();
-    }
-    reversed_platform
-}
-
-fn tilt_west(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    let mut tilted_platform: Vec<Vec<char>> = platform.to_vec();
-
-    for row in tilted_platform.iter_mut() {
+//! Day 14: Parabolic Reflector Dish.
+//!
+//! [`solve_part_two`] spins the platform for a number of cycles, with the cache it finds the
+//! cycle of the platforms with [`cycle::brent`] and skips whole periods.
+
+use crate::{
+    cycle,
+    grid::Grid,
+    input::{self, ParseError},
+    solution::Solution,
+};
+
+fn tilt_west(platform: &Grid<char>) -> Grid<char> {
+    let mut tilted_platform = platform.clone();
+
+    for row_index in 0..tilted_platform.height() {
+        let row = tilted_platform.row_mut(row_index);
         let mut i = 0;
         while i < row.len() {
             let square_stone_index = row[i..].iter().position(|&tile| tile == '#');
             let mut next_stone = row.len();
-            if square_stone_index.is_some() {
-                next_stone = i + square_stone_index.unwrap()
+            if let Some(square_stone_index) = square_stone_index {
+                next_stone = i + square_stone_index
             }
             let round_stone_count = row[i..next_stone]
                 .iter()
@@ -46,129 +39,146 @@ fn tilt_west(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
     tilted_platform
 }
 
-fn tilt_north(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    flip_to_col(&tilt_west(&flip_to_col(platform)))
+fn tilt_north(platform: &Grid<char>) -> Grid<char> {
+    tilt_west(&platform.transpose()).transpose()
 }
 
-fn tilt_east(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    reverse_platform(&tilt_west(&reverse_platform(&platform)))
+fn tilt_east(platform: &Grid<char>) -> Grid<char> {
+    tilt_west(&platform.mirror_left_right()).mirror_left_right()
 }
 
-fn tilt_south(platform: &Vec<Vec<char>>) -> Vec<Vec<char>> {
-    flip_to_col(&tilt_east(&flip_to_col(platform)))
+fn tilt_south(platform: &Grid<char>) -> Grid<char> {
+    tilt_east(&platform.transpose()).transpose()
 }
 
-fn calculate_load(platform: &Vec<Vec<char>>) -> usize {
+fn calculate_load(platform: &Grid<char>) -> usize {
     platform
-        .iter()
+        .rows()
         .enumerate()
         .map(|(row_index, row)| {
-            row.iter().filter(|tile| **tile == 'O').count() * (platform.len() - row_index)
+            row.iter().filter(|tile| **tile == 'O').count() * (platform.height() - row_index)
         })
         .sum()
 }
 
-fn solve_part_one(platform: &Vec<Vec<char>>) -> usize {
+pub fn solve_part_one(platform: &Grid<char>) -> usize {
     calculate_load(&tilt_north(platform))
 }
 
-fn solve_part_two(platform: &Vec<Vec<char>>, cycles: i128, cached: bool) -> usize {
-    let mut tilted_platform = platform.to_vec();
-    let mut i = 0;
-    let mut cache: HashMap<(Vec<Vec<char>>, i128), i128> = HashMap::new();
-    let tilts = cycles * 4;
-    let mut skipped = false;
-    while i < tilts {
-        let direction = i % 4;
-        if cached && !skipped && cache.contains_key(&(tilted_platform.clone(), direction)) {
-            let cached_index = cache[&(tilted_platform.clone(), direction)];
-            let skip = i - cached_index;
-            let todo = tilts - i;
-            i += (todo / skip) as i128 * skip;
-            skipped = true;
-        }
+// North, west, south and east, `on_tilt` sees the platform after every tilt.
+fn spin_cycle(platform: &Grid<char>, on_tilt: &mut dyn FnMut(&Grid<char>)) -> Grid<char> {
+    let mut tilted_platform = platform.clone();
+    for tilt in [tilt_north, tilt_west, tilt_south, tilt_east] {
+        tilted_platform = tilt(&tilted_platform);
+        on_tilt(&tilted_platform);
+    }
+    tilted_platform
+}
 
-        cache.insert((tilted_platform.clone(), direction), i);
+/// The platform after `cycles` spin cycles, `on_tilt` sees the platform after every tilt.
+pub fn spin_cycles(
+    platform: &Grid<char>,
+    cycles: usize,
+    on_tilt: &mut dyn FnMut(&Grid<char>),
+) -> Grid<char> {
+    let mut tilted_platform = platform.clone();
+    for _ in 0..cycles {
+        tilted_platform = spin_cycle(&tilted_platform, on_tilt);
+    }
+    tilted_platform
+}
 
-        match direction {
-            0 => tilted_platform = tilt_north(&tilted_platform),
-            1 => tilted_platform = tilt_west(&tilted_platform),
-            2 => tilted_platform = tilt_south(&tilted_platform),
-            3 => tilted_platform = tilt_east(&tilted_platform),
-            _ => panic!("Modulo is broken"),
-        }
-        i += 1;
+/// The platform after `cycles` spin cycles, whole periods of the repeating platforms are skipped.
+pub fn platform_after(platform: &Grid<char>, cycles: usize) -> Grid<char> {
+    cycle::state_after(
+        platform,
+        |platform| spin_cycle(platform, &mut |_| {}),
+        cycles,
+    )
+}
+
+/// Load after `cycles` spin cycles, `cached` skips ahead once the platform repeats.
+pub fn solve_part_two(platform: &Grid<char>, cycles: usize, cached: bool) -> usize {
+    if cached {
+        calculate_load(&platform_after(platform, cycles))
+    } else {
+        calculate_load(&spin_cycles(platform, cycles, &mut |_| {}))
     }
-    calculate_load(&tilted_platform)
 }
 
-fn get_input(file: &str) -> Vec<Vec<char>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\n")
-        .into_iter()
-        .map(|s| s.to_owned().chars().collect::<Vec<char>>())
-        .collect()
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    input::parse_grid(Day14::DAY, input, ".#O")
 }
 
-pub fn solver() {
-    let input = get_input("./src/day14/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input, 1000000000, true);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Grid<char>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input, 1000000000, true).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day14_example_input_part_one() {
-        let input = get_input("./src/day14/example_input.txt");
+        let input = get_input("./src/day14/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(136, sum_part_one);
     }
 
     #[test]
     fn day14_input_part_one() {
-        let input = get_input("./src/day14/input.txt");
+        let input = get_input("./src/day14/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(108840, sum_part_one);
     }
 
     #[test]
     fn day14_example_input_part_two_cache_test() {
-        let input = get_input("./src/day14/example_input.txt");
+        let input = get_input("./src/day14/example_input.txt").unwrap();
         let sum_part_two_cached = solve_part_two(&input, 1231, true);
         let sum_part_two = solve_part_two(&input, 1231, false);
         assert_eq!(sum_part_two, sum_part_two_cached);
     }
 
+    #[test]
+    fn day14_cache_skip_ending_on_the_last_tilt() {
+        let input = parse(".O\nOO").unwrap();
+        assert_eq!(
+            solve_part_two(&input, 1000, false),
+            solve_part_two(&input, 1000, true)
+        );
+    }
+
     #[test]
     fn day14_example_input_part_two() {
-        let input = get_input("./src/day14/example_input.txt");
+        let input = get_input("./src/day14/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 1000000000, true);
         assert_eq!(64, sum_part_two);
     }
 
     #[test]
     fn day14_input_part_two() {
-        let input = get_input("./src/day14/input.txt");
+        let input = get_input("./src/day14/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 1000000000, true);
         assert_eq!(103445, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day14/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day14/input.txt");
-        b.iter(|| solve_part_two(&input, 1000000000, true))
-    }
 }
@@ -1,4 +1,11 @@
-use std::{collections::HashMap, fs};
+//! Day 15: Lens Library.
+
+use std::collections::HashMap;
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
 
 struct Lens {
     label: String,
@@ -7,20 +14,20 @@ struct Lens {
 
 fn get_hash(label: &String) -> usize {
     let mut hash = 0;
-    label.chars().into_iter().for_each(|symbol| {
+    label.chars().for_each(|symbol| {
         let ascii = symbol as u8;
         hash += ascii as usize;
-        hash = hash * 17;
-        hash = hash % 256;
+        hash *= 17;
+        hash %= 256;
     });
     hash
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
-    input.iter().map(|code| get_hash(code)).sum()
+pub fn solve_part_one(input: &Vec<String>) -> usize {
+    input.iter().map(get_hash).sum()
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
+pub fn solve_part_two(input: &Vec<String>) -> usize {
     let mut boxes: HashMap<usize, Vec<Lens>> = HashMap::new();
     input.iter().for_each(|lens_code| {
         if lens_code.contains('-') {
@@ -28,12 +35,12 @@ fn solve_part_two(input: &Vec<String>) -> usize {
                 .split('-')
                 .map(|s| s.to_owned())
                 .collect::<Vec<String>>()[0];
-            let box_number = get_hash(&label);
+            let box_number = get_hash(label);
             if boxes.contains_key(&box_number) {
                 let lens_box = &boxes[&box_number];
                 let pos = lens_box.iter().position(|lens| lens.label == *label);
-                if pos.is_some() {
-                    boxes.get_mut(&box_number).unwrap().remove(pos.unwrap());
+                if let Some(pos) = pos {
+                    boxes.get_mut(&box_number).unwrap().remove(pos);
                 }
             }
         } else {
@@ -47,18 +54,13 @@ fn solve_part_two(input: &Vec<String>) -> usize {
                 label: label.to_string(),
                 focal_length,
             };
-            let box_number = get_hash(&label);
-            if boxes.contains_key(&box_number) {
-                let lens_box = &boxes[&box_number];
-                let pos = lens_box.iter().position(|lens| lens.label == *label);
-                if pos.is_some() {
-                    boxes.get_mut(&box_number).unwrap().remove(pos.unwrap());
-                    boxes.get_mut(&box_number).unwrap().insert(pos.unwrap(), new_lens);
-                }else{
-                    boxes.get_mut(&box_number).unwrap().push(new_lens);
-                }
+            let box_number = get_hash(label);
+            let lens_box = boxes.entry(box_number).or_default();
+            let pos = lens_box.iter().position(|lens| lens.label == *label);
+            if let Some(pos) = pos {
+                lens_box[pos] = new_lens;
             } else {
-                boxes.insert(box_number, vec![new_lens]);
+                lens_box.push(new_lens);
             }
         }
     });
@@ -79,66 +81,76 @@ fn solve_part_two(input: &Vec<String>) -> usize {
         .sum()
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input.split("\n").collect::<Vec<&str>>()[0]
-        .trim()
-        .split(",")
-        .into_iter()
-        .map(|s| s.to_owned())
+fn parse_steps(line: Line) -> Result<Vec<String>, ParseError> {
+    line.text()
+        .split(',')
+        .map(|step| {
+            if let Some((_, focal_length)) = step.split_once('=') {
+                line.parse::<usize>(focal_length, "a focal length")?;
+            } else if !step.ends_with('-') {
+                return Err(line.error(step, "a step 'label=focal length' or 'label-'"));
+            }
+            Ok(step.to_owned())
+        })
         .collect()
 }
 
-pub fn solver() {
-    let input = get_input("./src/day15/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    Ok(input::parse_lines(Day15::DAY, input, parse_steps)?.concat())
+}
+
+pub fn get_input(file: &str) -> Result<Vec<String>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day15_example_input_part_one() {
-        let input = get_input("./src/day15/example_input.txt");
+        let input = get_input("./src/day15/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(1320, sum_part_one);
     }
 
     #[test]
     fn day15_input_part_one() {
-        let input = get_input("./src/day15/input.txt");
+        let input = get_input("./src/day15/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(504036, sum_part_one);
     }
 
     #[test]
     fn day15_example_input_part_two() {
-        let input = get_input("./src/day15/example_input.txt");
+        let input = get_input("./src/day15/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(145, sum_part_two);
     }
 
     #[test]
     fn day15_input_part_two() {
-        let input = get_input("./src/day15/input.txt");
+        let input = get_input("./src/day15/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(295719, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day15/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day15/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
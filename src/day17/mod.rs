@@ -1,26 +1,35 @@
+//! Day 17: Clumsy Crucible.
+//!
+//! Both parts are an A* search over position, direction and straight steps, the ultra
+//! crucible of part two has to move four to ten blocks before turning or stopping.
+
 use pathfinding::prelude::astar;
-use std::fs;
+
+use crate::{
+    geometry::{Dir, Point},
+    grid::Grid,
+    input::{self, ParseError},
+    solution::Solution,
+    trace,
+};
 
 #[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 struct Pos {
-    position: (i128, i128),
-    direction: (i128, i128),
+    position: Point,
+    direction: Dir,
     straight_steps: i128,
 }
 
 impl Pos {
-    fn distance_simple(&self, other: &(i128, i128)) -> i128 {
-        (self.position.0.abs_diff(other.0) + self.position.1.abs_diff(other.1)) as i128
+    fn distance_simple(&self, other: &Point) -> i128 {
+        self.position.manhattan(*other)
     }
 
-    fn successors(&self, layout: &Vec<Vec<i128>>) -> Vec<(Pos, i128)> {
+    fn successors(&self, layout: &Grid<i128>) -> Vec<(Pos, i128)> {
         let mut next_positions: Vec<Pos> = Vec::new();
         if self.straight_steps < 3 {
-            let next_pos = (
-                self.position.0 + self.direction.0,
-                self.position.1 + self.direction.1,
-            );
-            if in_bounds(layout, &next_pos) {
+            let next_pos = self.position + self.direction;
+            if layout.in_bounds(next_pos) {
                 let straight_pos = Pos {
                     position: next_pos,
                     direction: self.direction,
@@ -30,234 +39,163 @@ impl Pos {
             }
         }
 
-        let left_turn = (-self.direction.1, -self.direction.0);
-        let next_pos = (self.position.0 + left_turn.0, self.position.1 + left_turn.1);
-        if in_bounds(layout, &next_pos) {
-            let left_pos = Pos {
-                position: next_pos,
-                direction: left_turn,
-                straight_steps: 1,
-            };
-
-            next_positions.push(left_pos);
-        }
-
-        let right_turn = (self.direction.1, self.direction.0);
-        let next_pos = (
-            self.position.0 + right_turn.0,
-            self.position.1 + right_turn.1,
-        );
-        if in_bounds(layout, &next_pos) {
-            let right_pos = Pos {
-                position: next_pos,
-                direction: right_turn,
-                straight_steps: 1,
-            };
-
-            next_positions.push(right_pos);
+        for turn in [self.direction.turn_left(), self.direction.turn_right()] {
+            let next_pos = self.position + turn;
+            if layout.in_bounds(next_pos) {
+                let turn_pos = Pos {
+                    position: next_pos,
+                    direction: turn,
+                    straight_steps: 1,
+                };
+                next_positions.push(turn_pos);
+            }
         }
 
         next_positions
             .iter()
-            .map(|p| {
-                (
-                    p.clone(),
-                    layout[p.position.0 as usize][p.position.1 as usize],
-                )
-            })
+            .map(|p| (p.clone(), layout[p.position]))
             .collect()
     }
 
-    fn ultra_successors(&self, layout: &Vec<Vec<i128>>, goal: (i128, i128)) -> Vec<(Pos, i128)> {
+    fn ultra_successors(&self, layout: &Grid<i128>, goal: Point) -> Vec<(Pos, i128)> {
         let mut next_positions: Vec<Pos> = Vec::new();
         if self.straight_steps < 10 {
-            let next_pos = (
-                self.position.0 + self.direction.0,
-                self.position.1 + self.direction.1,
-            );
-            if in_bounds(layout, &next_pos) {
+            let next_pos = self.position + self.direction;
+            if layout.in_bounds(next_pos) {
                 let straight_pos = Pos {
                     position: next_pos,
                     direction: self.direction,
                     straight_steps: self.straight_steps + 1,
                 };
-                if !(next_pos.0 == goal.0 && next_pos.1 == goal.1) {
-                    next_positions.push(straight_pos);
-                } else if straight_pos.straight_steps >= 4 {
+                if next_pos != goal || straight_pos.straight_steps >= 4 {
                     next_positions.push(straight_pos);
                 }
             }
         }
 
         if self.straight_steps >= 4 {
-            let left_turn = (-self.direction.1, -self.direction.0);
-            let next_pos = (self.position.0 + left_turn.0, self.position.1 + left_turn.1);
-            if in_bounds(layout, &next_pos) {
-                let left_pos = Pos {
-                    position: next_pos,
-                    direction: left_turn,
-                    straight_steps: 1,
-                };
-                if !(next_pos.0 == goal.0 && next_pos.1 == goal.1) {
-                    next_positions.push(left_pos);
-                }
-            }
-
-            let right_turn = (self.direction.1, self.direction.0);
-            let next_pos = (
-                self.position.0 + right_turn.0,
-                self.position.1 + right_turn.1,
-            );
-            if in_bounds(layout, &next_pos) {
-                let right_pos = Pos {
-                    position: next_pos,
-                    direction: right_turn,
-                    straight_steps: 1,
-                };
-                if !(next_pos.0 == goal.0 && next_pos.1 == goal.1) {
-                    next_positions.push(right_pos);
+            for turn in [self.direction.turn_left(), self.direction.turn_right()] {
+                let next_pos = self.position + turn;
+                if layout.in_bounds(next_pos) {
+                    let turn_pos = Pos {
+                        position: next_pos,
+                        direction: turn,
+                        straight_steps: 1,
+                    };
+                    if next_pos != goal {
+                        next_positions.push(turn_pos);
+                    }
                 }
             }
         }
 
         next_positions
             .iter()
-            .map(|p| {
-                (
-                    p.clone(),
-                    layout[p.position.0 as usize][p.position.1 as usize],
-                )
-            })
+            .map(|p| (p.clone(), layout[p.position]))
             .collect()
     }
 }
 
-fn in_bounds(height_map: &Vec<Vec<i128>>, position: &(i128, i128)) -> bool {
-    return 0 <= position.0
-        && position.0 < height_map.len() as i128
-        && 0 <= position.1
-        && position.1 < height_map.first().unwrap().len() as i128;
-}
-
-fn solve_part_one(layout: &Vec<Vec<i128>>) -> i128 {
-    let goal: (i128, i128) = (
-        (layout.len() - 1) as i128,
-        (layout[layout.len() - 1].len() - 1) as i128,
-    );
+/// The path with the least heat loss from the top left to the bottom right block and its heat
+/// loss, `ultra` selects the ultra crucibles of part two.
+pub fn find_best_path(layout: &Grid<i128>, ultra: bool) -> (Vec<Point>, i128) {
+    let goal = Point::new(layout.height() as i128 - 1, layout.width() as i128 - 1);
     let start: Pos = Pos {
-        position: (0, 0),
-        direction: (0, 1),
+        position: Point::new(0, 0),
+        direction: Dir::Right,
         straight_steps: 0,
     };
-    let result = astar(
+    let (path, heat_loss) = astar(
         &start,
-        |p| p.successors(layout),
+        |p| {
+            if ultra {
+                p.ultra_successors(layout, goal)
+            } else {
+                p.successors(layout)
+            }
+        },
         |p| p.distance_simple(&goal) / 3,
         |p| p.position == goal,
-    );
-    result.unwrap().1
+    )
+    .unwrap();
+    let path: Vec<Point> = path.iter().map(|pos| pos.position).collect();
+    trace!("path: {:?}", path);
+    (path, heat_loss)
 }
 
-fn solve_part_two(layout: &Vec<Vec<i128>>) -> i128 {
-    let goal: (i128, i128) = (
-        (layout.len() - 1) as i128,
-        (layout[layout.len() - 1].len() - 1) as i128,
-    );
-    let start: Pos = Pos {
-        position: (0, 0),
-        direction: (0, 1),
-        straight_steps: 0,
-    };
-    let result = astar(
-        &start,
-        |p| p.ultra_successors(layout, goal),
-        |p| p.distance_simple(&goal) / 3,
-        |p| p.position == goal,
-    );
-    println!(
-        "{:?}",
-        result
-            .clone()
-            .unwrap()
-            .0
-            .iter()
-            .map(|pos| pos.position)
-            .collect::<Vec<(i128, i128)>>()
-    );
-    result.unwrap().1
+pub fn solve_part_one(layout: &Grid<i128>) -> i128 {
+    find_best_path(layout, false).1
+}
+
+pub fn solve_part_two(layout: &Grid<i128>) -> i128 {
+    find_best_path(layout, true).1
+}
+
+pub fn parse(input: &str) -> Result<Grid<i128>, ParseError> {
+    input::parse_grid_with(Day17::DAY, input, |line, symbol| {
+        line.parse::<i128>(symbol, "a heat loss digit")
+    })
 }
 
-fn get_input(file: &str) -> Vec<Vec<i128>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| {
-            s.to_owned()
-                .chars()
-                .map(|symbol| symbol.to_digit(10).unwrap() as i128)
-                .collect::<Vec<i128>>()
-        })
-        .collect()
+pub fn get_input(file: &str) -> Result<Grid<i128>, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day17/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+    type Input = Grid<i128>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day17_example_input_part_one() {
-        let input = get_input("./src/day17/example_input.txt");
+        let input = get_input("./src/day17/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(102, sum_part_one);
     }
 
     #[test]
     fn day17_input_part_one() {
-        let input = get_input("./src/day17/input.txt");
+        let input = get_input("./src/day17/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(886, sum_part_one);
     }
 
     #[test]
     fn day17_example_input_part_two() {
-        let input = get_input("./src/day17/example_input.txt");
+        let input = get_input("./src/day17/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(94, sum_part_two);
     }
 
     #[test]
     fn day17_example_input_two_part_two() {
-        let input = get_input("./src/day17/example_input_two.txt");
+        let input = get_input("./src/day17/example_input_two.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(71, sum_part_two);
     }
 
     #[test]
     fn day17_input_part_two() {
-        let input = get_input("./src/day17/input.txt");
+        let input = get_input("./src/day17/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(1055, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day17/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day17/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
@@ -1,249 +1,251 @@
+//! Day 18: Lavaduct Lagoon.
+//!
+//! Part one draws the trench as pipes and counts the enclosed tiles, part two computes the area of
+//! the polygon with the shoelace formula and Pick's theorem.
+
 use num::abs;
-use std::{
-    cmp::{max, min},
-    fs,
+use std::cmp::{max, min};
+
+use crate::{
+    day10::{count_enclosed_spaces, pipe_between},
+    geometry::{Dir, Point},
+    grid::Grid,
+    input::{self, Line, ParseError},
+    solution::Solution,
 };
 
-fn count_enclosed_spaces(input_map: &Vec<Vec<char>>) -> usize {
-    let mut map = input_map.clone();
-    for row in 0..map.len() {
-        let mut is_inside = false;
-        let mut last_wall = ' ';
-        for col in 0..map[row].len() {
-            if map[row][col] == '0' || map[row][col] == '.' {
-                if !is_inside {
-                    map[row][col] = '0';
-                }
-                continue;
-            }
-            match (last_wall, map[row][col]) {
-                (_, '|') => is_inside = !is_inside,
-                (_, 'L') | (_, 'F') => last_wall = map[row][col],
-                ('L', '7') | ('F', 'J') => {
-                    is_inside = !is_inside;
-                    last_wall = ' '
-                }
-                ('L', 'J') | ('F', '7') => last_wall = ' ',
-                (_, '-') => (),
-                (_, _) => panic!("weird layout {} {}", last_wall, map[row][col]),
-            }
-        }
-        // println!("{:?}", map[row]);
-    }
-
-    map.iter()
-        .map(|row| {
-            row.iter()
-                .filter(|tile| **tile == '.')
-                .collect::<Vec<&char>>()
-                .len()
-        })
-        .sum()
+/// A line of the dig plan, part two reads direction and steps from the colour code instead.
+pub struct Instruction {
+    pub direction: Dir,
+    pub steps: i128,
+    pub color_direction: Dir,
+    pub color_steps: i128,
 }
 
-fn solve_part_one(instructions: &Vec<Vec<String>>) -> usize {
-    let mut layout: Vec<(i128, i128)> = Vec::new();
-    let mut current_pos = (0, 0);
+/// The trench of the dig plan drawn with pipes, its top left corner is the top left corner of the
+/// trench.
+pub fn dig_trench(instructions: &Vec<Instruction>) -> Grid<char> {
+    let mut layout: Vec<Point> = Vec::new();
+    let mut current_pos = Point::new(0, 0);
     layout.push(current_pos);
-    instructions.iter().for_each(|command| {
-        let direction_string = command[0].chars().collect::<Vec<char>>()[0];
-        let steps = command[1].parse::<i128>().unwrap();
-        let direction: (i128, i128);
-        match direction_string {
-            'R' => direction = (0, 1),
-            'D' => direction = (1, 0),
-            'L' => direction = (0, -1),
-            'U' => direction = (-1, 0),
-            _ => panic!("Invalid command"),
-        }
-        for _ in 0..steps {
-            current_pos = (current_pos.0 + direction.0, current_pos.1 + direction.1);
+    instructions.iter().for_each(|instruction| {
+        for _ in 0..instruction.steps {
+            current_pos = current_pos + instruction.direction;
             layout.push(current_pos);
         }
     });
 
-    let mut bounding_box: ((i128, i128), (i128, i128)) =
-        ((i128::MAX, i128::MAX), (i128::MIN, i128::MIN));
+    let mut bounding_box: (Point, Point) = (
+        Point::new(i128::MAX, i128::MAX),
+        Point::new(i128::MIN, i128::MIN),
+    );
     for pos in &layout {
-        bounding_box.0 = (min(bounding_box.0 .0, pos.0), min(bounding_box.0 .1, pos.1));
-        bounding_box.1 = (max(bounding_box.1 .0, pos.0), max(bounding_box.1 .1, pos.1));
+        bounding_box.0 = Point::new(
+            min(bounding_box.0.row, pos.row),
+            min(bounding_box.0.col, pos.col),
+        );
+        bounding_box.1 = Point::new(
+            max(bounding_box.1.row, pos.row),
+            max(bounding_box.1.col, pos.col),
+        );
     }
 
-    bounding_box.1 .0 = bounding_box.1 .0 - bounding_box.0 .0;
-    bounding_box.1 .1 = bounding_box.1 .1 - bounding_box.0 .1;
-
-    let mut map: Vec<Vec<char>> =
-        vec![vec!['.'; (bounding_box.1 .1 + 1) as usize]; (bounding_box.1 .0 + 1) as usize];
+    let size = bounding_box.1 - bounding_box.0;
+    let mut map = Grid::new((size.col + 1) as usize, (size.row + 1) as usize, '.');
     layout.pop();
     for i in 0..layout.len() {
         let last_pos = layout[(i + layout.len() - 1) % layout.len()];
         let next_pos = layout[(i + 1) % layout.len()];
         let cur_pos = layout[i];
 
-        let next_dir = (next_pos.0 - cur_pos.0, next_pos.1 - cur_pos.1);
-        let last_dir = (cur_pos.0 - last_pos.0, cur_pos.1 - last_pos.1);
-
-        let symbol: char;
-        match (last_dir, next_dir) {
-            ((_, 0), (_, 0)) => symbol = '|',
-            ((0, _), (0, _)) => symbol = '-',
-            ((1, 0), (0, 1)) | ((0, -1), (-1, 0)) => symbol = 'L',
-            ((1, 0), (0, -1)) | ((0, 1), (-1, 0)) => symbol = 'J',
-            ((-1, 0), (0, -1)) | ((0, 1), (1, 0)) => symbol = '7',
-            ((-1, 0), (0, 1)) | ((0, -1), (1, 0)) => symbol = 'F',
-            _ => panic!("Weird layout"),
-        }
-        map[(cur_pos.0 - bounding_box.0 .0) as usize][(cur_pos.1 - bounding_box.0 .1) as usize] =
-            symbol
+        let next_dir = Dir::from_offset(next_pos - cur_pos).unwrap();
+        let last_dir = Dir::from_offset(cur_pos - last_pos).unwrap();
+
+        let symbol = pipe_between(last_dir.reverse(), next_dir).expect("Weird layout");
+        map[cur_pos - bounding_box.0] = symbol
     }
+    map
+}
 
-    layout.len() + count_enclosed_spaces(&map)
+pub fn solve_part_one(instructions: &Vec<Instruction>) -> usize {
+    let map = dig_trench(instructions);
+    map.iter().filter(|(_, symbol)| **symbol != '.').count() + count_enclosed_spaces(&map)
 }
 
-fn calculate_signed_area(corners: &Vec<(i128, i128)>) -> i128 {
+fn calculate_signed_area(corners: &Vec<Point>) -> i128 {
     let mut total_area = 0;
     for i in 2..corners.len() {
         let one = corners[i];
         let two = corners[i - 1];
-        let area: i128 = one.0 * two.1 - one.1 * two.0;
+        let area: i128 = one.row * two.col - one.col * two.row;
         total_area += area;
     }
     total_area / 2
 }
 
-fn calculate_area(commands: &Vec<((i128, i128), i128)>) -> usize {
-    let mut corners: Vec<(i128, i128)> = Vec::new();
-    let mut total_steps = 0;
-    corners.push((0, 0));
+// Corners of the polygon starting at the origin, the closing corner is not repeated.
+fn find_corners(commands: &Vec<(Dir, i128)>) -> Vec<Point> {
+    let mut corners: Vec<Point> = Vec::new();
+    corners.push(Point::new(0, 0));
     commands.iter().for_each(|(direction, steps)| {
         let last_corner = *corners.last().unwrap();
-        let next_corner = (
-            last_corner.0 + direction.0 * steps,
-            last_corner.1 + direction.1 * steps,
-        );
-        total_steps += steps;
-        corners.push(next_corner);
+        corners.push(last_corner + direction.offset() * *steps);
     });
     corners.pop();
+    corners
+}
+
+/// Corners of the trench of part one, the dig plan starts at the origin.
+pub fn trench_corners(instructions: &Vec<Instruction>) -> Vec<Point> {
+    let commands: Vec<(Dir, i128)> = instructions
+        .iter()
+        .map(|instruction| (instruction.direction, instruction.steps))
+        .collect();
+    find_corners(&commands)
+}
+
+fn calculate_area(commands: &Vec<(Dir, i128)>) -> usize {
+    let corners = find_corners(commands);
+    let total_steps: i128 = commands.iter().map(|(_, steps)| steps).sum();
 
     (total_steps as f64 * 0.5) as usize + abs(calculate_signed_area(&corners)) as usize + 1
 }
 
-#[cfg(test)]
-fn solve_part_one_with_part_two_solver(instructions: &Vec<Vec<String>>) -> usize {
-    let mut commands: Vec<((i128, i128), i128)> = Vec::new();
-
-    instructions.iter().for_each(|command| {
-        let direction_string = command[0].chars().collect::<Vec<char>>()[0];
-        let steps = command[1].parse::<i128>().unwrap();
-        let direction: (i128, i128);
-        match direction_string {
-            'R' => direction = (0, 1),
-            'D' => direction = (1, 0),
-            'L' => direction = (0, -1),
-            'U' => direction = (-1, 0),
-            _ => panic!("Invalid command"),
-        }
-        commands.push((direction, steps));
-    });
+/// Same as [`solve_part_one`], using the polygon area of part two on the plain instructions.
+pub fn solve_part_one_with_part_two_solver(instructions: &Vec<Instruction>) -> usize {
+    let commands: Vec<(Dir, i128)> = instructions
+        .iter()
+        .map(|instruction| (instruction.direction, instruction.steps))
+        .collect();
 
     calculate_area(&commands)
 }
 
-fn solve_part_two(instructions: &Vec<Vec<String>>) -> usize {
-    let mut commands: Vec<((i128, i128), i128)> = Vec::new();
-
-    instructions.iter().for_each(|command| {
-        let hex = command[2][2..(command[2].len() - 1)].to_owned();
-        let direction_char = hex.chars().last().unwrap();
-        let steps = i128::from_str_radix(&hex[..hex.len() - 1], 16).unwrap();
-        let direction: (i128, i128);
-        match direction_char {
-            '0' => direction = (0, 1),
-            '1' => direction = (1, 0),
-            '2' => direction = (0, -1),
-            '3' => direction = (-1, 0),
-            _ => panic!("Invalid command"),
-        }
-        commands.push((direction, steps));
-    });
+pub fn solve_part_two(instructions: &Vec<Instruction>) -> usize {
+    let commands: Vec<(Dir, i128)> = instructions
+        .iter()
+        .map(|instruction| (instruction.color_direction, instruction.color_steps))
+        .collect();
 
     calculate_area(&commands)
 }
 
-fn get_input(file: &str) -> Vec<Vec<String>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.split_whitespace().map(|s| s.to_owned()).collect())
-        .collect()
+// A single character `token` read with `from_symbol`.
+fn parse_direction(
+    line: Line,
+    token: &str,
+    from_symbol: fn(char) -> Option<Dir>,
+    expected: &str,
+) -> Result<Dir, ParseError> {
+    let mut symbols = token.chars();
+    match (symbols.next().and_then(from_symbol), symbols.next()) {
+        (Some(direction), None) => Ok(direction),
+        _ => Err(line.error(token, expected)),
+    }
+}
+
+fn parse_instruction(line: Line) -> Result<Instruction, ParseError> {
+    let mut split = line.text().split_whitespace();
+    let direction = line.next(&mut split, "a direction U, D, L or R")?;
+    let direction = parse_direction(
+        line,
+        direction,
+        Dir::from_letter,
+        "a direction U, D, L or R",
+    )?;
+    let steps = line.parse::<i128>(line.next(&mut split, "steps")?, "steps")?;
+    let color = line.next(&mut split, "a colour code (#rrggbb)")?;
+    let hex = color
+        .strip_prefix("(#")
+        .and_then(|color| color.strip_suffix(')'))
+        .filter(|hex| hex.len() == 6)
+        .ok_or_else(|| line.error(color, "a colour code (#rrggbb)"))?;
+    let color_steps = i128::from_str_radix(&hex[..5], 16)
+        .map_err(|_| line.error(&hex[..5], "five hexadecimal digits"))?;
+    let color_direction = parse_direction(
+        line,
+        &hex[5..],
+        Dir::from_hex_digit,
+        "a direction 0, 1, 2 or 3",
+    )?;
+    Ok(Instruction {
+        direction,
+        steps,
+        color_direction,
+        color_steps,
+    })
+}
+
+pub fn parse(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    input::parse_lines(Day18::DAY, input, parse_instruction)
 }
 
-pub fn solver() {
-    let input = get_input("./src/day18/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Vec<Instruction>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day18_example_input_part_one() {
-        let input = get_input("./src/day18/example_input.txt");
+        let input = get_input("./src/day18/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(62, sum_part_one);
     }
 
     #[test]
     fn day18_input_part_one() {
-        let input = get_input("./src/day18/input.txt");
+        let input = get_input("./src/day18/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(46394, sum_part_one);
     }
 
     #[test]
     fn day18_example_input_part_one_with_part_two_solver() {
-        let input = get_input("./src/day18/example_input.txt");
+        let input = get_input("./src/day18/example_input.txt").unwrap();
         let sum_part_one = solve_part_one_with_part_two_solver(&input);
         assert_eq!(62, sum_part_one);
     }
 
     #[test]
     fn day18_input_part_one_with_part_two_solver() {
-        let input = get_input("./src/day18/input.txt");
+        let input = get_input("./src/day18/input.txt").unwrap();
         let sum_part_one = solve_part_one_with_part_two_solver(&input);
         assert_eq!(46394, sum_part_one);
     }
 
     #[test]
     fn day18_example_input_part_two() {
-        let input = get_input("./src/day18/example_input.txt");
+        let input = get_input("./src/day18/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(952408144115, sum_part_two);
     }
 
     #[test]
     fn day18_input_part_two() {
-        let input = get_input("./src/day18/input.txt");
+        let input = get_input("./src/day18/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(201398068194715, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day18/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day18/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
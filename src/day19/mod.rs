@@ -1,26 +1,41 @@
+//! Day 19: Aplenty.
+//!
+//! Part two pushes ranges of ratings through the workflows with
+//! [`check_workflow_with_range`] instead of single parts.
+
 use std::{
     cmp::{max, min},
     collections::HashMap,
-    fs,
 };
 
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+/// The workflows by name and the ratings of every part.
+pub type System = (HashMap<String, Workflow>, Vec<Rating>);
+
+/// The `x`, `m`, `a` and `s` ratings of a part.
+pub type Rating = HashMap<char, i128>;
+
+/// The tests of a workflow in order and where parts go that pass none of them, built by [`parse`].
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Workflow {
+pub struct Workflow {
     tests: Vec<Test>,
     fallback: String,
 }
 
+/// A rule like `a<2006:qkq`, parts that pass it continue with `next_workflow`.
 #[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Test {
+pub struct Test {
     attribute: char,
     compare: char,
     compare_to: i128,
     next_workflow: String,
 }
 
-fn solve_part_one(
-    (workflows, parts): &(HashMap<String, Workflow>, Vec<HashMap<char, i128>>),
-) -> i128 {
+pub fn solve_part_one((workflows, parts): &System) -> i128 {
     parts
         .iter()
         .map(|part| {
@@ -34,20 +49,20 @@ fn solve_part_one(
                     match test.compare {
                         '<' => test_passed = part_attribute < test.compare_to,
                         '>' => test_passed = part_attribute > test.compare_to,
-                        _ => panic!("Compare symbol"),
+                        _ => unreachable!("comparisons are checked while parsing"),
                     }
                     if test_passed {
                         next_workflow = Some(test.next_workflow.to_owned());
                         break;
                     }
                 }
-                if next_workflow.is_some() {
-                    workflow_name = next_workflow.unwrap();
+                if let Some(next_workflow) = next_workflow {
+                    workflow_name = next_workflow;
                 } else {
                     workflow_name = current_workflow.fallback.to_owned();
                 }
                 if workflow_name == "A" {
-                    return part.iter().map(|(_, value)| value).sum();
+                    return part.values().sum();
                 } else if workflow_name == "R" {
                     return 0;
                 }
@@ -56,16 +71,19 @@ fn solve_part_one(
         .sum()
 }
 
-fn get_combinations(accepted_parts: &HashMap<char, (i128, i128)>) -> usize {
+/// Number of rating combinations within the exclusive bounds of `accepted_parts`.
+pub fn get_combinations(accepted_parts: &HashMap<char, (i128, i128)>) -> usize {
     let mut combinations = 1;
-    for (_, (lower, upper)) in accepted_parts {
+    for (lower, upper) in accepted_parts.values() {
         let possibilities = max(upper - lower - 1, 0) as usize;
         combinations *= possibilities;
     }
     combinations
 }
 
-fn check_workflow_with_range(
+/// Number of accepted rating combinations that enter `workflow_name` with the exclusive bounds
+/// of `accepted_parts` for `x`, `m`, `a` and `s`.
+pub fn check_workflow_with_range(
     workflows: &HashMap<String, Workflow>,
     workflow_name: &String,
     accepted_parts: &HashMap<char, (i128, i128)>,
@@ -92,159 +110,167 @@ fn check_workflow_with_range(
                 current_attribute.0 = min(current_attribute.0, test.compare_to + 1);
                 current_attribute.1 = min(current_attribute.1, test.compare_to + 1);
             }
-            _ => panic!("Compare symbol"),
+            _ => unreachable!("comparisons are checked while parsing"),
         }
         if recursive_attribute.0 + 1 < recursive_attribute.1 {
-            if test.next_workflow == "A"{
+            if test.next_workflow == "A" {
                 sum += get_combinations(&recursive_accepted_parts);
-            }
-            else if test.next_workflow  != "R" {
-                sum += check_workflow_with_range(workflows, &test.next_workflow, &recursive_accepted_parts);
+            } else if test.next_workflow != "R" {
+                sum += check_workflow_with_range(
+                    workflows,
+                    &test.next_workflow,
+                    &recursive_accepted_parts,
+                );
             }
         }
-        if !(current_attribute.0 + 1 < current_attribute.1) {
+        if current_attribute.0 + 1 >= current_attribute.1 {
             return sum;
         }
     }
     if workflow.fallback == "A" {
         sum += get_combinations(&current_accepted_parts);
-    }
-    else if workflow.fallback != "R" {
+    } else if workflow.fallback != "R" {
         sum += check_workflow_with_range(workflows, &workflow.fallback, &current_accepted_parts)
     }
     sum
 }
 
-fn solve_part_two((workflows, _): &(HashMap<String, Workflow>, Vec<HashMap<char, i128>>)) -> usize {
+pub fn solve_part_two((workflows, _): &System) -> usize {
     let mut accepted_parts: HashMap<char, (i128, i128)> = HashMap::new();
     accepted_parts.insert('x', (0, 4001));
     accepted_parts.insert('m', (0, 4001));
     accepted_parts.insert('a', (0, 4001));
     accepted_parts.insert('s', (0, 4001));
-    check_workflow_with_range(&workflows, &"in".to_owned(), &accepted_parts)
+    check_workflow_with_range(workflows, &"in".to_owned(), &accepted_parts)
 }
 
-fn get_input(file: &str) -> (HashMap<String, Workflow>, Vec<HashMap<char, i128>>) {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let input: Vec<Vec<String>> = input
-        .split("\r\n\r\n")
+fn parse_attribute(line: Line, attribute: &str) -> Result<char, ParseError> {
+    match attribute {
+        "x" | "m" | "a" | "s" => Ok(attribute.chars().next().unwrap()),
+        _ => Err(line.error(attribute, "a category x, m, a or s")),
+    }
+}
+
+fn parse_test(line: Line, test_string: &str) -> Result<Test, ParseError> {
+    let (condition, next_workflow) = line.split_once(test_string, ":")?;
+    let attribute = parse_attribute(line, condition.get(..1).unwrap_or(condition))?;
+    let compare = condition.get(1..2).unwrap_or(&condition[condition.len()..]);
+    if compare != "<" && compare != ">" {
+        return Err(line.error(compare, "a comparison '<' or '>'"));
+    }
+    Ok(Test {
+        attribute,
+        compare: compare.chars().next().unwrap(),
+        compare_to: line.parse::<i128>(&condition[2..], "a rating")?,
+        next_workflow: next_workflow.to_owned(),
+    })
+}
+
+fn parse_workflow(line: Line) -> Result<(String, Workflow), ParseError> {
+    let (workflow_name, test_strings) = line.split_once(line.text(), "{")?;
+    let (test_strings, _) = line.split_once(test_strings, "}")?;
+    let mut test_strings = test_strings.split(',').collect::<Vec<&str>>();
+    let fallback = test_strings.pop().unwrap().to_owned();
+    let tests = test_strings
         .into_iter()
-        .map(|s| s.split("\r\n").map(|s| s.to_owned()).collect())
-        .collect();
-    let workflow_strings = &input[0];
-    let part_strings = &input[1];
+        .map(|test_string| parse_test(line, test_string))
+        .collect::<Result<Vec<Test>, ParseError>>()?;
+    Ok((workflow_name.to_owned(), Workflow { tests, fallback }))
+}
+
+fn parse_part(line: Line) -> Result<Rating, ParseError> {
+    let part_string = line
+        .text()
+        .strip_prefix('{')
+        .and_then(|part_string| part_string.strip_suffix('}'))
+        .ok_or_else(|| line.error(line.text(), "a part '{x=..,m=..,a=..,s=..}'"))?;
+    let attributes = part_string
+        .split(',')
+        .map(|attribute_string| {
+            let (attribute, rating) = line.split_once(attribute_string, "=")?;
+            Ok((
+                parse_attribute(line, attribute)?,
+                line.parse::<i128>(rating, "a rating")?,
+            ))
+        })
+        .collect::<Result<Rating, ParseError>>()?;
+    if attributes.len() != 4 {
+        return Err(line.error(line.end(), "ratings for x, m, a and s"));
+    }
+    Ok(attributes)
+}
+
+pub fn parse(input: &str) -> Result<System, ParseError> {
     let mut workflows: HashMap<String, Workflow> = HashMap::new();
-    let mut parts: Vec<HashMap<char, i128>> = Vec::new();
-
-    workflow_strings.iter().for_each(|workflow_string| {
-        let mut chars = workflow_string.chars();
-        chars.next_back();
-        let workflow_string = chars.as_str();
-        let split = workflow_string.split("{").collect::<Vec<&str>>();
-        let workflow_name = split[0].to_owned();
-        let mut tests: Vec<Test> = Vec::new();
-        let test_strings = split[1].split(",").collect::<Vec<&str>>();
-        for i in 0..test_strings.len() - 1 {
-            let test_string = test_strings[i];
-            let test_split = test_string.split(':').collect::<Vec<&str>>();
-            let next_workflow = test_split[1].to_owned();
-            if test_split[0].contains('<') {
-                let test_split = test_split[0].split('<').collect::<Vec<&str>>();
-                tests.push(Test {
-                    attribute: test_split[0].chars().next().unwrap(),
-                    compare: '<',
-                    compare_to: test_split[1].parse::<i128>().unwrap(),
-                    next_workflow,
-                })
-            } else {
-                let test_split = test_split[0].split('>').collect::<Vec<&str>>();
-                tests.push(Test {
-                    attribute: test_split[0].chars().next().unwrap(),
-                    compare: '>',
-                    compare_to: test_split[1].parse::<i128>().unwrap(),
-                    next_workflow,
-                })
-            }
+    let mut parts: Vec<Rating> = Vec::new();
+    // Workflows come first, the parts follow after a blank line.
+    let mut reading_parts = false;
+    input::parse_lines(Day19::DAY, input, |line| {
+        if line.text().is_empty() {
+            reading_parts = true;
+        } else if reading_parts {
+            parts.push(parse_part(line)?);
+        } else {
+            let (workflow_name, workflow) = parse_workflow(line)?;
+            workflows.insert(workflow_name, workflow);
         }
-        workflows.insert(
-            workflow_name,
-            Workflow {
-                tests,
-                fallback: test_strings.last().unwrap().to_owned().to_owned(),
-            },
-        );
-    });
-
-    part_strings.iter().for_each(|part_string| {
-        let mut chars = part_string.chars();
-        chars.next();
-        chars.next_back();
-        let part_string = chars.as_str();
-        let part_split = part_string.split(',').collect::<Vec<&str>>();
-        let mut attributes: HashMap<char, i128> = HashMap::new();
-        part_split.iter().for_each(|attribute_string| {
-            let attribute_split = attribute_string.split('=').collect::<Vec<&str>>();
-            attributes.insert(
-                attribute_split[0].chars().next().unwrap(),
-                attribute_split[1].parse::<i128>().unwrap(),
-            );
-        });
-        parts.push(attributes);
-    });
-
-    (workflows, parts)
+        Ok(())
+    })?;
+    Ok((workflows, parts))
+}
+
+pub fn get_input(file: &str) -> Result<System, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day19/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+    type Input = System;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day19_example_input_part_one() {
-        let input = get_input("./src/day19/example_input.txt");
+        let input = get_input("./src/day19/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(19114, sum_part_one);
     }
 
     #[test]
     fn day19_input_part_one() {
-        let input = get_input("./src/day19/input.txt");
+        let input = get_input("./src/day19/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(342650, sum_part_one);
     }
 
     #[test]
     fn day19_example_input_part_two() {
-        let input = get_input("./src/day19/example_input.txt");
+        let input = get_input("./src/day19/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(167409079868000, sum_part_two);
     }
 
     #[test]
     fn day19_input_part_two() {
-        let input = get_input("./src/day19/input.txt");
+        let input = get_input("./src/day19/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(130303473508222, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day19/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day19/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
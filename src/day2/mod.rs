@@ -1,75 +1,205 @@
-use std::{cmp::max, fs};
+//! Day 2: Cube Conundrum.
+//!
+//! Every [`Game`] keeps its draws in order, so a game can tell which draw made it impossible for a
+//! bag as well as the smallest bag it fits into. Colours are whatever the input names, the puzzle
+//! only uses red, green and blue.
 
-fn get_sum_of_cube_power(games: &Vec<(u32, (u32, u32, u32))>) -> u32 {
-    return games
+use std::{any::Any, cmp::max, collections::BTreeMap};
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::{Part, Puzzle, Solution},
+};
+
+/// Cubes by colour shown at once, also used for the cubes in a bag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Draw {
+    pub cubes: BTreeMap<String, u32>,
+}
+
+impl<const N: usize> From<[(&str, u32); N]> for Draw {
+    fn from(cubes: [(&str, u32); N]) -> Draw {
+        Draw {
+            cubes: cubes
+                .into_iter()
+                .map(|(colour, count)| (colour.to_owned(), count))
+                .collect(),
+        }
+    }
+}
+
+impl Draw {
+    /// 0 for colours that were not drawn.
+    pub fn count(&self, colour: &str) -> u32 {
+        self.cubes.get(colour).copied().unwrap_or(0)
+    }
+
+    /// The colours in alphabetical order.
+    pub fn colours(&self) -> Vec<&str> {
+        self.cubes.keys().map(|colour| colour.as_str()).collect()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.cubes.values().sum()
+    }
+
+    /// Whether a bag with the cubes of `bag` can show this draw, colours missing from the bag
+    /// cannot be drawn.
+    pub fn fits(&self, bag: &Draw) -> bool {
+        self.cubes
+            .iter()
+            .all(|(colour, count)| *count <= bag.count(colour))
+    }
+
+    /// The product of the counts of `colours`.
+    pub fn power(&self, colours: &[&str]) -> u32 {
+        colours.iter().map(|colour| self.count(colour)).product()
+    }
+}
+
+/// The bag of the puzzle, 12 red, 13 green and 14 blue cubes.
+pub fn puzzle_bag() -> Draw {
+    Draw::from([("red", 12), ("green", 13), ("blue", 14)])
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Game {
+    pub id: u32,
+    pub draws: Vec<Draw>,
+}
+
+impl Game {
+    /// The fewest cubes of every colour the game can be played with.
+    pub fn minimal_bag(&self) -> Draw {
+        let mut bag = Draw::default();
+        for (colour, count) in self.draws.iter().flat_map(|draw| &draw.cubes) {
+            let most = bag.cubes.entry(colour.to_owned()).or_insert(0);
+            *most = max(*most, *count);
+        }
+        bag
+    }
+
+    /// The index of the first draw that `bag` cannot show, `None` if the game is possible.
+    pub fn first_impossible_draw(&self, bag: &Draw) -> Option<usize> {
+        self.draws.iter().position(|draw| !draw.fits(bag))
+    }
+
+    pub fn is_possible(&self, bag: &Draw) -> bool {
+        self.first_impossible_draw(bag).is_none()
+    }
+
+    /// The number of cubes shown in every draw.
+    pub fn draw_totals(&self) -> Vec<u32> {
+        self.draws.iter().map(Draw::total).collect()
+    }
+}
+
+/// The sum of the powers of the minimal bags over `colours`, a colour a game never shows makes
+/// its power 0.
+pub fn get_sum_of_cube_power(games: &Vec<Game>, colours: &[&str]) -> u32 {
+    games
         .iter()
-        .map(|(_, (red, green, blue))| red * green * blue)
-        .sum();
+        .map(|game| game.minimal_bag().power(colours))
+        .sum()
 }
 
-fn get_sum_of_possible_game_ids(
-    games: &Vec<(u32, (u32, u32, u32))>,
-    (max_red, max_green, max_blue): (u32, u32, u32),
-) -> u32 {
-    return games
+pub fn get_sum_of_possible_game_ids(games: &Vec<Game>, bag: &Draw) -> u32 {
+    games
         .iter()
-        .filter(|(_, (red, green, blue))| {
-            red <= &max_red && green <= &max_green && blue <= &max_blue
-        })
-        .map(|(game_id, _)| game_id)
-        .sum();
-}
-
-fn parse_games(input: Vec<String>) -> Vec<(u32, (u32, u32, u32))> {
-    // id, (red, green, blue)
-    let mut games: Vec<(u32, (u32, u32, u32))> = Vec::new();
-    for line in input {
-        let split: Vec<&str> = line.split(":").collect();
-        let game_id = split[0]
-            .split(" ")
-            .collect::<Vec<&str>>()
-            .last()
-            .unwrap()
-            .parse::<u32>()
-            .unwrap();
-        let games_string: Vec<&str> = split[1].split(";").into_iter().map(|s| s.trim()).collect();
-        let (mut red, mut green, mut blue): (u32, u32, u32) = (0, 0, 0);
-        for game in games_string {
-            let colors_string: Vec<&str> = game.split(",").into_iter().map(|s| s.trim()).collect();
-            for color in colors_string {
-                let split_color: Vec<&str> = color.split(" ").collect();
-                let color_count = split_color[0].parse::<u32>().unwrap();
-                match split_color[1] {
-                    "red" => red = max(red, color_count),
-                    "green" => green = max(green, color_count),
-                    "blue" => blue = max(blue, color_count),
-                    _ => panic!("color mismatch"),
-                }
-            }
+        .filter(|game| game.is_possible(bag))
+        .map(|game| game.id)
+        .sum()
+}
+
+// The cubes of a comma separated list like `3 blue, 4 red`, repeated colours add up.
+fn parse_cubes(line: Line, text: &str, draw: &mut Draw) -> Result<(), ParseError> {
+    for color in text.split(',').map(|s| s.trim()) {
+        let Some((color_count, color_name)) = color.split_once(' ') else {
+            return Err(line.error(color, "a cube count and a colour"));
+        };
+        let color_count = line.parse::<u32>(color_count, "a cube count")?;
+        let color_name = color_name.trim();
+        if color_name.is_empty() {
+            return Err(line.error(color_name, "a colour"));
         }
-        games.push((game_id, (red, green, blue)));
+        *draw.cubes.entry(color_name.to_owned()).or_insert(0) += color_count;
     }
+    Ok(())
+}
+
+fn parse_game(line: Line) -> Result<Game, ParseError> {
+    let (game, draws) = line.split_once(line.text(), ":")?;
+    let id = line.parse::<u32>(game.rsplit(' ').next().unwrap(), "a game id")?;
+    let draws = draws
+        .split(';')
+        .map(|text| {
+            let mut draw = Draw::default();
+            parse_cubes(line, text, &mut draw)?;
+            Ok(draw)
+        })
+        .collect::<Result<Vec<Draw>, ParseError>>()?;
+    Ok(Game { id, draws })
+}
 
-    return games;
+/// A bag written like a draw, `12 red, 13 green, 14 blue`, the colours may also be spread over
+/// several lines.
+pub fn parse_bag(text: &str) -> Result<Draw, ParseError> {
+    let mut bag = Draw::default();
+    input::parse_lines(Day2::DAY, text, |line| {
+        parse_cubes(line, line.text(), &mut bag)
+    })?;
+    Ok(bag)
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let lines: Vec<String> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return lines;
+pub fn parse(input: &str) -> Result<Vec<Game>, ParseError> {
+    input::parse_lines(Day2::DAY, input, parse_game)
+}
+
+pub fn get_input(file: &str) -> Result<Vec<Game>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+    type Input = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        get_sum_of_possible_game_ids(input, &puzzle_bag()).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(get_sum_of_cube_power(input, &puzzle_bag().colours()).to_string())
+    }
 }
 
-pub fn solver() {
-    let input = get_input("./src/day2/input.txt");
-    let games = parse_games(input);
-    let sum_part_one = get_sum_of_possible_game_ids(&games, (12, 13, 14));
-    let sum_part_two = get_sum_of_cube_power(&games);
-    println!("Part 1: {sum_part_one}");
-    println!("Part 2: {sum_part_two}");
+/// Day 2 with another bag, part two multiplies the colours of that bag.
+pub struct WithBag(pub Draw);
+
+impl Puzzle for WithBag {
+    fn day(&self) -> u32 {
+        Day2::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Option<String> {
+        let games = input
+            .downcast_ref::<Vec<Game>>()
+            .expect("Input was not parsed by day 2");
+        let answer = match part {
+            Part::One => get_sum_of_possible_game_ids(games, &self.0),
+            Part::Two => get_sum_of_cube_power(games, &self.0.colours()),
+        };
+        Some(answer.to_string())
+    }
 }
 
 #[cfg(test)]
@@ -78,33 +208,76 @@ mod tests {
 
     #[test]
     fn day2_example_input_part_one() {
-        let input = get_input("./src/day2/example_input.txt");
-        let games = parse_games(input);
-        let sum = get_sum_of_possible_game_ids(&games, (12, 13, 14));
+        let games = get_input("./src/day2/example_input.txt").unwrap();
+        let sum = get_sum_of_possible_game_ids(&games, &puzzle_bag());
         assert_eq!(8, sum);
     }
 
     #[test]
     fn day2_example_input_part_two() {
-        let input = get_input("./src/day2/example_input.txt");
-        let games = parse_games(input);
-        let power = get_sum_of_cube_power(&games);
+        let games = get_input("./src/day2/example_input.txt").unwrap();
+        let power = get_sum_of_cube_power(&games, &["red", "green", "blue"]);
         assert_eq!(2286, power);
     }
 
     #[test]
     fn day2_input_part_one() {
-        let input = get_input("./src/day2/input.txt");
-        let games = parse_games(input);
-        let sum = get_sum_of_possible_game_ids(&games, (12, 13, 14));
+        let games = get_input("./src/day2/input.txt").unwrap();
+        let sum = get_sum_of_possible_game_ids(&games, &puzzle_bag());
         assert_eq!(2268, sum);
     }
 
     #[test]
     fn day2_input_part_two() {
-        let input = get_input("./src/day2/input.txt");
-        let games = parse_games(input);
-        let power = get_sum_of_cube_power(&games);
+        let games = get_input("./src/day2/input.txt").unwrap();
+        let power = get_sum_of_cube_power(&games, &["red", "green", "blue"]);
         assert_eq!(63542, power);
     }
+
+    #[test]
+    fn day2_game_queries() {
+        let games = get_input("./src/day2/example_input.txt").unwrap();
+        let bag = puzzle_bag();
+        // Game 3 shows 20 red cubes in its first draw, game 4 15 blue cubes in its last.
+        let impossible: Vec<(u32, Option<usize>)> = games
+            .iter()
+            .map(|game| (game.id, game.first_impossible_draw(&bag)))
+            .collect();
+        assert_eq!(
+            vec![(1, None), (2, None), (3, Some(0)), (4, Some(2)), (5, None)],
+            impossible
+        );
+        assert_eq!(
+            Draw::from([("red", 4), ("green", 2), ("blue", 6)]),
+            games[0].minimal_bag()
+        );
+        assert_eq!(vec![7, 9, 2], games[0].draw_totals());
+    }
+
+    #[test]
+    fn day2_any_colours() {
+        let games = parse("Game 1: 2 purple, 1 red; 3 purple\nGame 2: 1 light blue").unwrap();
+        let bag = parse_bag("3 purple\n1 red, 1 light blue").unwrap();
+        assert_eq!(vec!["light blue", "purple", "red"], bag.colours());
+        assert_eq!(3, get_sum_of_possible_game_ids(&games, &bag));
+        assert_eq!(
+            1,
+            get_sum_of_possible_game_ids(&games, &Draw::from([("purple", 3), ("red", 1)]))
+        );
+        // Game 2 shows no purple cubes at all.
+        assert_eq!(3, get_sum_of_cube_power(&games, &["purple", "red"]));
+        // Without colours every game multiplies nothing.
+        assert_eq!(1 + 1, get_sum_of_cube_power(&games, &[]));
+        assert!(parse_bag("12 red, 13").is_err());
+
+        let games = get_input("./src/day2/input.txt").unwrap();
+        let answers: Vec<Option<String>> = [Part::One, Part::Two]
+            .iter()
+            .map(|part| WithBag(puzzle_bag()).solve(&games, *part))
+            .collect();
+        assert_eq!(
+            vec![Some("2268".to_owned()), Some("63542".to_owned())],
+            answers
+        );
+    }
 }
@@ -1,20 +1,37 @@
-use num::Integer;
+//! Day 20: Pulse Propagation.
+//!
+//! Part two finds the cycle of the network feeding every input of the module before `rx` with
+//! [`cycle::brent`] and the first button press in which all of them send a high pulse.
+
 use std::{
     collections::{HashMap, HashSet, VecDeque},
-    fmt, fs,
+    fmt,
+};
+
+use crate::{
+    cycle::{self, Schedule},
+    debug,
+    input::{self, Line, ParseError},
+    solution::Solution,
+    warn,
 };
 
+/// The outputs of the broadcaster and the flip-flop and conjunction modules by name.
+pub type Configuration = (Vec<String>, HashMap<String, Module>);
+
+/// A pulse sent from one module to another.
 #[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 
-struct Signal {
-    from: String,
-    to: String,
-    high_pulse: bool, // false: low pulse
+pub struct Signal {
+    pub from: String,
+    pub to: String,
+    pub high_pulse: bool, // false: low pulse
 }
 
+/// A flip-flop (`%`) or conjunction (`&`) module with its state, built by [`parse`].
 #[derive(Clone, PartialEq, Eq)]
 
-struct Module {
+pub struct Module {
     prefix: char,
     name: String,
     input_memory: HashMap<String, bool>, // false: low pulse
@@ -22,6 +39,28 @@ struct Module {
     output: Vec<String>,
 }
 
+impl Module {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// `%name on` or `%name off` for a flip-flop, `&name` with the remembered pulse of every input
+    /// as `1` (high) or `0` (low) for a conjunction, inputs sorted by name.
+    pub fn state(&self) -> String {
+        if self.prefix == '%' {
+            let flip = if self.flip { "on" } else { "off" };
+            return format!("%{} {flip}", self.name);
+        }
+        let mut inputs: Vec<(&String, &bool)> = self.input_memory.iter().collect();
+        inputs.sort();
+        let memory: String = inputs
+            .iter()
+            .map(|(_, high_pulse)| if **high_pulse { '1' } else { '0' })
+            .collect();
+        format!("&{} {memory}", self.name)
+    }
+}
+
 impl fmt::Display for Module {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.prefix == '%' {
@@ -45,60 +84,11 @@ impl fmt::Display for Module {
     }
 }
 
-fn parse_input(input: &Vec<String>) -> (Vec<String>, HashMap<String, Module>) {
-    let mut broadcaster: Vec<String> = Vec::new();
-    let mut modules: HashMap<String, Module> = HashMap::new();
-    input.iter().for_each(|module_description| {
-        let split: Vec<&str> = module_description.split("->").collect();
-        let name = split[0].trim();
-        let output: Vec<String> = split[1].trim().split(", ").map(|s| s.to_owned()).collect();
-        if name == "broadcaster" {
-            broadcaster = output;
-            return;
-        }
-        let mut chars = name.chars();
-        let prefix = chars.next().unwrap();
-        let name = chars.as_str().to_owned();
-        let module = Module {
-            prefix,
-            name: name.to_owned(),
-            input_memory: HashMap::new(),
-            flip: false,
-            output,
-        };
-        modules.insert(name, module);
-    });
-    broadcaster.iter().for_each(|output| {
-        if modules[output].prefix == '&' {
-            modules
-                .get_mut(output)
-                .unwrap()
-                .input_memory
-                .insert("broadcaster".to_owned(), false);
-        }
-    });
-
-    for (_, module) in modules.clone() {
-        module.output.iter().for_each(|output| {
-            if modules.contains_key(output) && modules[output].prefix == '&' {
-                modules
-                    .get_mut(output)
-                    .unwrap()
-                    .input_memory
-                    .insert(module.name.to_owned(), false);
-            }
-        });
-    }
-
-    (broadcaster, modules)
-}
-
 fn process_button_press(
     broadcaster: &Vec<String>,
     modules: &mut HashMap<String, Module>,
-    module_name_of_interest: &String,
-) -> ((usize, usize), HashSet<String>) {
-    let mut fullfilled: HashSet<String> = HashSet::new();
+    on_signal: &mut dyn FnMut(&Signal, &HashMap<String, Module>),
+) -> (usize, usize) {
     let mut num_pulses: (usize, usize) = (0, 0);
     num_pulses.0 += 1;
     let mut signals_to_process: VecDeque<Signal> = VecDeque::new();
@@ -119,12 +109,14 @@ fn process_button_press(
         }
 
         if !modules.contains_key(&signal.to) {
+            on_signal(&signal, modules);
             continue;
         }
 
         let module = modules.get_mut(&signal.to).unwrap();
         if module.prefix == '%' {
             if signal.high_pulse {
+                on_signal(&signal, modules);
                 continue;
             }
             module.flip = !module.flip;
@@ -142,7 +134,7 @@ fn process_button_press(
             }
 
             let mut next_pulse: bool = false;
-            for (_, high_pulse_input) in &module.input_memory {
+            for high_pulse_input in module.input_memory.values() {
                 if !high_pulse_input {
                     next_pulse = true;
                     break;
@@ -157,122 +149,235 @@ fn process_button_press(
                 signals_to_process.push_back(next_signal);
             });
         }
-        if signal.to == *module_name_of_interest {
-            for (memory_module_name, memory_high_pulse) in &module.input_memory {
-                if *memory_high_pulse {
-                    fullfilled.insert(memory_module_name.to_owned());
-                }
-            }
-        }
+        on_signal(&signal, modules);
     }
-    (num_pulses, fullfilled)
+    num_pulses
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
-    let (broadcaster, mut modules) = parse_input(&input);
+pub fn solve_part_one((broadcaster, modules): &Configuration) -> usize {
+    let mut modules = modules.clone();
     let mut num_pulses: (usize, usize) = (0, 0);
 
     for _ in 0..1000 {
-        let (pulses, _) = process_button_press(&broadcaster, &mut modules, &"".to_owned());
+        let pulses = process_button_press(broadcaster, &mut modules, &mut |_, _| {});
         num_pulses.0 += pulses.0;
         num_pulses.1 += pulses.1;
     }
-    println!("count: {}, {}", num_pulses.0, num_pulses.1);
+    debug!("count: {}, {}", num_pulses.0, num_pulses.1);
 
     num_pulses.0 * num_pulses.1
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
-    let (broadcaster, mut modules) = parse_input(&input);
-    let mut button_presses: usize = 0;
-
-    let mut conjunction_modules: Vec<(Module, bool)> = Vec::new();
-    let mut pre_goal_module: Option<String> = None;
-    let mut pre_goal_input_length: Option<usize> = None;
-    for (_, module) in &modules {
-        if module.output.contains(&"rx".to_owned()) {
-            conjunction_modules.push((module.clone(), false));
-            pre_goal_module = Some(module.name.to_owned());
-            pre_goal_input_length = Some(module.input_memory.len());
-            break;
+/// Presses the button `presses` times, `on_signal` sees every pulse with the modules after it
+/// arrived. Pulses from the button to the broadcaster are left out.
+pub fn press_button(
+    (broadcaster, modules): &Configuration,
+    presses: usize,
+    on_signal: &mut dyn FnMut(&Signal, &HashMap<String, Module>),
+) {
+    let mut modules = modules.clone();
+    for _ in 0..presses {
+        process_button_press(broadcaster, &mut modules, on_signal);
+    }
+}
+
+// `name` and the modules that send pulses to it, directly or through other modules.
+fn find_feeding_modules(modules: &HashMap<String, Module>, name: &str) -> HashSet<String> {
+    let mut feeding: HashSet<String> = HashSet::new();
+    let mut todo: Vec<&str> = vec![name];
+    while let Some(next) = todo.pop() {
+        if !modules.contains_key(next) || !feeding.insert(next.to_owned()) {
+            continue;
+        }
+        todo.extend(
+            modules
+                .values()
+                .filter(|module| module.output.iter().any(|output| output == next))
+                .map(|module| module.name.as_str()),
+        );
+    }
+    feeding
+}
+
+// The button presses, counted from 0, in which `name` sends a high pulse. `name` and the modules
+// feeding it are a network of their own, whose states repeat.
+fn find_high_pulse_schedule(
+    broadcaster: &Vec<String>,
+    modules: &HashMap<String, Module>,
+    name: &str,
+) -> Schedule {
+    let feeding = find_feeding_modules(modules, name);
+    let broadcaster: Vec<String> = broadcaster
+        .iter()
+        .filter(|output| feeding.contains(*output))
+        .cloned()
+        .collect();
+    let network: HashMap<String, Module> = modules
+        .iter()
+        .filter(|(module_name, _)| feeding.contains(*module_name))
+        .map(|(module_name, module)| (module_name.to_owned(), module.clone()))
+        .collect();
+    let press = |network: &HashMap<String, Module>| {
+        let mut network = network.clone();
+        let mut high_pulse = false;
+        process_button_press(&broadcaster, &mut network, &mut |signal, _| {
+            high_pulse |= signal.from == name && signal.high_pulse;
+        });
+        (network, high_pulse)
+    };
+    let cycle = cycle::brent(&network, |network| press(network).0);
+
+    let mut events: Vec<usize> = Vec::new();
+    let mut state = network;
+    for presses in 0..cycle.prefix + cycle.period {
+        let (next_state, high_pulse) = press(&state);
+        if high_pulse {
+            events.push(presses);
         }
+        state = next_state;
+    }
+    Schedule { cycle, events }
+}
+
+/// `None` if no module sends pulses to `rx`, or its inputs never send high pulses at once, or only
+/// after more than 2^64 presses.
+pub fn solve_part_two((broadcaster, modules): &Configuration) -> Option<usize> {
+    let Some(pre_goal_module) = modules
+        .values()
+        .find(|module| module.output.contains(&"rx".to_owned()))
+    else {
+        warn!("no module sends pulses to rx");
+        return None;
+    };
+    let schedules: Vec<Schedule> = pre_goal_module
+        .input_memory
+        .keys()
+        .map(|input| find_high_pulse_schedule(broadcaster, modules, input))
+        .collect();
+    for (input, schedule) in pre_goal_module.input_memory.keys().zip(&schedules) {
+        debug!(
+            "{input} sends high pulses in presses {:?} of {:?}",
+            schedule.events, schedule.cycle
+        );
+    }
+    let presses = cycle::first_common_event(&schedules).map(|press| press + 1);
+    if presses.is_none() {
+        warn!(
+            "the inputs of rx never send high pulses at once, or only after more than 2^64 presses"
+        );
+    }
+    presses
+}
+
+// name, outputs and the module, `None` for the broadcaster
+fn parse_module(line: Line) -> Result<(String, Vec<String>, Option<Module>), ParseError> {
+    let (name, output) = line.split_once(line.text(), "->")?;
+    let name = name.trim();
+    let output: Vec<String> = output.trim().split(", ").map(|s| s.to_owned()).collect();
+    if name == "broadcaster" {
+        return Ok((name.to_owned(), output, None));
+    }
+    let prefix = name.get(..1).unwrap_or(name);
+    if prefix != "%" && prefix != "&" {
+        return Err(line.error(prefix, "a module type '%' or '&', or 'broadcaster'"));
     }
-    let pre_goal_module = pre_goal_module.unwrap();
-    let pre_goal_input_length = pre_goal_input_length.unwrap();
-    // println!("{pre_goal_module}");
-    let mut fulfilled_input: HashMap<String, usize> = HashMap::new();
-    loop {
-        button_presses += 1;
-        let (_, fulfilled_input_modules) =
-            process_button_press(&broadcaster, &mut modules, &pre_goal_module);
-        for fulfilled_input_module in fulfilled_input_modules {
-            fulfilled_input.insert(fulfilled_input_module, button_presses);
+    let name = name[1..].to_owned();
+    let module = Module {
+        prefix: prefix.chars().next().unwrap(),
+        name: name.to_owned(),
+        input_memory: HashMap::new(),
+        flip: false,
+        output: output.clone(),
+    };
+    Ok((name, output, Some(module)))
+}
+
+pub fn parse(input: &str) -> Result<Configuration, ParseError> {
+    let mut broadcaster: Vec<String> = Vec::new();
+    let mut modules: HashMap<String, Module> = HashMap::new();
+    for (name, output, module) in input::parse_lines(Day20::DAY, input, parse_module)? {
+        match module {
+            Some(module) => {
+                modules.insert(name, module);
+            }
+            None => broadcaster = output,
         }
-        if fulfilled_input.len() == pre_goal_input_length {
-            return fulfilled_input.iter().map(|(_, val)| *val).fold(1, |a, b| a.lcm(&b));
+    }
+    broadcaster.iter().for_each(|output| {
+        if let Some(module) = modules.get_mut(output) {
+            if module.prefix == '&' {
+                module.input_memory.insert("broadcaster".to_owned(), false);
+            }
         }
+    });
+
+    for (_, module) in modules.clone() {
+        module.output.iter().for_each(|output| {
+            if modules.contains_key(output) && modules[output].prefix == '&' {
+                modules
+                    .get_mut(output)
+                    .unwrap()
+                    .input_memory
+                    .insert(module.name.to_owned(), false);
+            }
+        });
     }
+
+    Ok((broadcaster, modules))
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect()
+pub fn get_input(file: &str) -> Result<Configuration, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day20/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+    type Input = Configuration;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        solve_part_two(input).map(|presses| presses.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day20_example_input_part_one() {
-        let input = get_input("./src/day20/example_input.txt");
+        let input = get_input("./src/day20/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(32000000, sum_part_one);
     }
 
     #[test]
     fn day20_input_part_one() {
-        let input = get_input("./src/day20/input.txt");
+        let input = get_input("./src/day20/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(821985143, sum_part_one);
     }
 
-    // #[test]
-    // fn day20_example_input_part_two() {
-    //     let input = get_input("./src/day20/example_input.txt");
-    //     let sum_part_two = solve_part_two(&input);
-    //     assert_eq!(167409079868000, sum_part_two);
-    // }
-
     #[test]
-    fn day20_input_part_two() {
-        let input = get_input("./src/day20/input.txt");
+    fn day20_example_input_part_two() {
+        let input = get_input("./src/day20/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
-        assert_eq!(240853834793347, sum_part_two);
+        assert_eq!(None, sum_part_two);
     }
 
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day20/input.txt");
-        b.iter(|| solve_part_one(&input))
+    #[test]
+    fn day20_input_part_two() {
+        let input = get_input("./src/day20/input.txt").unwrap();
+        let sum_part_two = solve_part_two(&input);
+        assert_eq!(Some(240853834793347), sum_part_two);
     }
-
-    // #[bench]
-    // fn bench_part_two(b: &mut Bencher) {
-    //     let input = get_input("./src/day20/input.txt");
-    //     b.iter(|| solve_part_two(&input))
-    // }
 }
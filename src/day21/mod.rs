@@ -1,114 +1,84 @@
+//! Day 21: Step Counter.
+//!
+//! Both parts take the number of steps, [`solve_part_two`] repeats the garden infinitely. Copies
+//! of the garden fill up with the same period, `finish_cycle` is that period (the garden width)
+//! and a value of 1 simulates every step instead of extrapolating.
+
 use std::{
-    collections::{HashMap, HashSet},
-    fs,
+    collections::{hash_map::Entry, BTreeSet, HashMap, HashSet},
+    mem,
 };
 
 use num::Integer;
 
+use crate::{
+    cycle::Detector,
+    debug,
+    geometry::{Dir, Point},
+    grid::Grid,
+    input::{self, ParseError},
+    log::{self, Level},
+    solution::Solution,
+    trace,
+};
+
+// A copy of the garden, it has converged once its reachable plots repeat.
 struct World {
     current_positions: HashSet<(usize, usize)>,
     last_result: usize,
-    last_last_result: usize,
+    detector: Detector<BTreeSet<(usize, usize)>>,
 }
 
-fn find_start(input: &Vec<Vec<char>>) -> (usize, usize) {
-    let mut start: Option<(usize, usize)> = None;
-    for row in 0..input.len() {
-        let line = &input[row];
-        for col in 0..line.len() {
-            if line[col] == 'S' {
-                start = Some((row, col));
-                break;
-            }
-        }
-        if start.is_some() {
-            break;
+impl World {
+    fn new(current_positions: HashSet<(usize, usize)>) -> World {
+        let mut detector = Detector::new();
+        detector.push(current_positions.iter().copied().collect());
+        World {
+            last_result: current_positions.len(),
+            current_positions,
+            detector,
         }
     }
-    start.unwrap()
 }
 
-fn get_neighbours(input: &Vec<Vec<char>>, pos: &(usize, usize)) -> Vec<(usize, usize)> {
-    let pos = (pos.0 as i128, pos.1 as i128);
-    let possible_neighbors: Vec<(i128, i128)> = vec![
-        (pos.0 - 1, pos.1),
-        (pos.0 + 1, pos.1),
-        (pos.0, pos.1 - 1),
-        (pos.0, pos.1 + 1),
-    ];
-    let mut neighbors: Vec<(usize, usize)> = Vec::new();
-    possible_neighbors.iter().for_each(|pos| {
-        if pos.0 < 0
-            || pos.0 >= input.len() as i128
-            || pos.1 < 0
-            || pos.1 >= input[pos.0 as usize].len() as i128
-        {
-            return;
-        }
-        let pos = (pos.0 as usize, pos.1 as usize);
-        if input[pos.0][pos.1] != '#' {
-            neighbors.push(pos);
-        }
-    });
-    neighbors
+fn find_start(input: &Grid<char>) -> (usize, usize) {
+    input
+        .position(|tile| *tile == 'S')
+        .expect("parse checks for the start")
 }
 
-fn convert_pos(input_size: &(usize, usize), pos: &(i128, i128)) -> (usize, usize) {
-    let mut row = pos.0;
-    if pos.0 < 0 {
-        let multiple = (-pos.0) as usize / input_size.0;
-        row += ((multiple + 1) * input_size.0) as i128;
-    }
-    let row = row as usize % input_size.0;
+fn get_neighbours(input: &Grid<char>, pos: &(usize, usize)) -> Vec<(usize, usize)> {
+    input
+        .neighbours(*pos)
+        .filter(|neighbour| input[*neighbour] != '#')
+        .collect()
+}
 
-    let mut col = pos.1;
-    if pos.1 < 0 {
-        let multiple = (-pos.1) as usize / input_size.1;
-        col += ((multiple + 1) * input_size.1) as i128;
-    }
-    let col = col as usize % input_size.1;
-    (row, col)
+fn convert_pos(input_size: &(usize, usize), pos: Point) -> (usize, usize) {
+    (
+        pos.row.rem_euclid(input_size.0 as i128) as usize,
+        pos.col.rem_euclid(input_size.1 as i128) as usize,
+    )
 }
 
+// The neighbours of `pos` on the repeated garden, with the direction of the neighbouring copy
+// when a step leaves the garden.
 fn get_neighbours_part_two(
-    input: &Vec<Vec<char>>,
+    input: &Grid<char>,
     pos: &(usize, usize),
-) -> Vec<((i128, i128), (usize, usize))> {
-    let possible_neighbors: Vec<(i128, i128)> = vec![(1, 0), (-1, 0), (0, 1), (0, -1)];
-    let mut neighbors: Vec<((i128, i128), (usize, usize))> = Vec::new();
-
-    possible_neighbors.iter().for_each(|delta| {
-        let converted_pos = convert_pos(
-            &(input.len(), input[0].len()),
-            &(pos.0 as i128 + delta.0, pos.1 as i128 + delta.1),
-        );
+) -> Vec<(Option<Dir>, (usize, usize))> {
+    let mut neighbors: Vec<(Option<Dir>, (usize, usize))> = Vec::new();
 
-        let mut world_delta: (i128, i128) = (0, 0);
-        match delta {
-            (1, 0) => {
-                if converted_pos.0 < pos.0 {
-                    world_delta = *delta;
-                }
-            }
-            (-1, 0) => {
-                if converted_pos.0 > pos.0 {
-                    world_delta = *delta;
-                }
-            }
-            (0, 1) => {
-                if converted_pos.1 < pos.1 {
-                    world_delta = *delta;
-                }
-            }
-            (0, -1) => {
-                if converted_pos.1 > pos.1 {
-                    world_delta = *delta;
-                }
-            }
-            _ => panic!("delta does not match"),
+    Dir::ALL.iter().for_each(|dir| {
+        let next_pos = Point::from(*pos) + *dir;
+        let converted_pos = convert_pos(&(input.height(), input.width()), next_pos);
+
+        let mut world_delta = None;
+        if Point::from(converted_pos) != next_pos {
+            world_delta = Some(*dir);
         }
 
-        if input[converted_pos.0][converted_pos.1] != '#' {
+        if input[converted_pos] != '#' {
             neighbors.push((world_delta, converted_pos));
         }
     });
@@ -116,43 +86,52 @@ fn get_neighbours_part_two(
 }
 
 fn get_result(
-    worlds: &HashMap<(i128, i128), World>,
-    finished_worlds: &HashMap<(i128, i128), (usize, usize)>,
+    worlds: &HashMap<Point, World>,
+    finished_worlds: &HashMap<Point, (usize, usize)>,
     step_is_even: bool,
 ) -> (usize, (usize, usize)) {
-    println!("get_result start");
+    debug!("get_result start");
     let mut result = 0;
     for (world_pos, world) in worlds {
-        if finished_worlds.contains_key(&world_pos) {
+        if finished_worlds.contains_key(world_pos) {
             continue;
         }
-        println!(
+        trace!(
             "World: ({}, {}) has result: {}",
-            world_pos.0, world_pos.1, world.last_result
+            world_pos.row,
+            world_pos.col,
+            world.last_result
         );
         result += world.last_result;
     }
 
-    let mut start_world = (0,0);
-    if finished_worlds.contains_key(&(0,0)){
-        start_world = finished_worlds[&(0, 0)];
+    let mut start_world = (0, 0);
+    if finished_worlds.contains_key(&Point::new(0, 0)) {
+        start_world = finished_worlds[&Point::new(0, 0)];
     }
     for (world_pos, (result_even, result_odd)) in finished_worlds {
         if step_is_even {
-            println!(
+            trace!(
                 "Even Finished world: ({}, {}) has result: {}",
-                world_pos.0, world_pos.1, result_even
+                world_pos.row,
+                world_pos.col,
+                result_even
             );
             result += result_even;
         } else {
-            println!(
+            trace!(
                 "Odd Finished world: ({}, {}) has result: {}",
-                world_pos.0, world_pos.1, result_odd
+                world_pos.row,
+                world_pos.col,
+                result_odd
             );
             result += result_odd;
         }
     }
-    println!("get_result end: result: {result}, start_world: ({},{})", start_world.0, start_world.1);
+    debug!(
+        "get_result end: result: {result}, start_world: ({},{})",
+        start_world.0, start_world.1
+    );
 
     (result, start_world)
 }
@@ -180,7 +159,12 @@ fn calculate_multiple_worlds(multiple: usize) -> (usize, usize, (usize, usize))
     )
 }
 
-fn solve_part_one(input: &Vec<Vec<char>>, steps: usize) -> usize {
+/// The garden plots reachable in exactly `steps` steps, `on_step` sees them after every step.
+pub fn reachable_plots(
+    input: &Grid<char>,
+    steps: usize,
+    on_step: &mut dyn FnMut(&HashSet<(usize, usize)>),
+) -> HashSet<(usize, usize)> {
     let start = find_start(input);
     let mut current_positions: HashSet<(usize, usize)> = HashSet::new();
     let mut neighbours: HashMap<(usize, usize), Vec<(usize, usize)>> = HashMap::new();
@@ -198,31 +182,33 @@ fn solve_part_one(input: &Vec<Vec<char>>, steps: usize) -> usize {
             });
         });
         current_positions = next_positions;
+        on_step(&current_positions);
         results.push(current_positions.len());
     }
     // println!("{:?}", results);
 
-    current_positions.len()
+    current_positions
+}
+
+/// Garden plots reachable in exactly `steps` steps.
+pub fn solve_part_one(input: &Grid<char>, steps: usize) -> usize {
+    reachable_plots(input, steps, &mut |_| {}).len()
 }
 
-fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) -> usize {
+/// Garden plots reachable in exactly `steps` steps on the infinitely repeated garden.
+pub fn solve_part_two(input: &Grid<char>, steps: usize, finish_cycle: usize) -> usize {
     let start = find_start(input);
     let mut current_positions: HashSet<(usize, usize)> = HashSet::new();
     current_positions.insert((start.0, start.1));
 
-    let mut worlds: HashMap<(i128, i128), World> = HashMap::new();
-    let start_world = World {
-        current_positions: current_positions.clone(),
-        last_result: 1,
-        last_last_result: 0,
-    };
-    worlds.insert((0, 0), start_world);
+    let mut worlds: HashMap<Point, World> = HashMap::new();
+    worlds.insert(Point::new(0, 0), World::new(current_positions));
 
-    let mut neighbours_delta: HashMap<(usize, usize), Vec<((i128, i128), (usize, usize))>> =
+    let mut neighbours_delta: HashMap<(usize, usize), Vec<(Option<Dir>, (usize, usize))>> =
         HashMap::new();
-    let mut finished_worlds: HashMap<(i128, i128), (usize, usize)> = HashMap::new();
+    let mut finished_worlds: HashMap<Point, (usize, usize)> = HashMap::new();
     let left_over = steps % finish_cycle;
-    println!("left_over: {left_over}");
+    debug!("left_over: {left_over}");
 
     let new_steps;
     if finish_cycle == 1 {
@@ -231,69 +217,47 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
         new_steps = finish_cycle * 2 + left_over + 1;
     }
     for step in 1..new_steps {
-        let mut new_worlds: HashMap<(i128, i128), World> = HashMap::new();
-        let mut next_other_world_positions: HashMap<(i128, i128), HashSet<(usize, usize)>> =
+        let mut new_worlds: HashMap<Point, World> = HashMap::new();
+        let mut next_other_world_positions: HashMap<Point, HashSet<(usize, usize)>> =
             HashMap::new();
-        for (world_pos, world) in &worlds {
+        for (world_pos, world) in worlds.iter_mut() {
             let mut next_positions: HashSet<(usize, usize)> = HashSet::new();
             world.current_positions.iter().for_each(|pos| {
                 if !neighbours_delta.contains_key(pos) {
                     neighbours_delta.insert(*pos, get_neighbours_part_two(input, pos));
                 }
-                neighbours_delta[&pos]
+                neighbours_delta[pos]
                     .iter()
                     .for_each(|(delta_world, new_pos)| {
-                        if delta_world.0 == 0 && delta_world.1 == 0 {
+                        let Some(delta_world) = delta_world else {
                             next_positions.insert(*new_pos);
                             return;
+                        };
+                        // Copies only spread away from the start garden.
+                        let offset = delta_world.offset();
+                        if world_pos.row * offset.row < 0 || world_pos.col * offset.col < 0 {
+                            return;
                         }
-                        match delta_world {
-                            (1, 0) => {
-                                if world_pos.0 < 0 {
-                                    return;
-                                }
-                            }
-                            (-1, 0) => {
-                                if world_pos.0 > 0 {
-                                    return;
-                                }
-                            }
-                            (0, 1) => {
-                                if world_pos.1 < 0 {
-                                    return;
-                                }
-                            }
-                            (0, -1) => {
-                                if world_pos.1 > 0 {
-                                    return;
-                                }
-                            }
-                            _ => panic!("delta_world does not match"),
-                        }
-                        let other_world_pos: (i128, i128) =
-                            (world_pos.0 + delta_world.0, world_pos.1 + delta_world.1);
+                        let other_world_pos = *world_pos + offset;
 
                         if !finished_worlds.contains_key(&other_world_pos) {
-                            if next_other_world_positions.contains_key(&other_world_pos) {
-                                next_other_world_positions
-                                    .get_mut(&other_world_pos)
-                                    .unwrap()
-                                    .insert(*new_pos);
-                            } else {
-                                let mut other_world_positions: HashSet<(usize, usize)> =
-                                    HashSet::new();
-                                other_world_positions.insert(*new_pos);
-                                next_other_world_positions
-                                    .insert(other_world_pos, other_world_positions);
-                            }
+                            next_other_world_positions
+                                .entry(other_world_pos)
+                                .or_default()
+                                .insert(*new_pos);
                         }
                     });
             });
             let result = next_positions.len();
-            if result == world.last_last_result {
-                println!(
-                    "World ({},{}) converged after {step} steps with result: {result}",
-                    world_pos.0, world_pos.1
+            // Plots reachable in an even and an odd number of steps alternate.
+            if let Some(cycle) = world
+                .detector
+                .push(next_positions.iter().copied().collect())
+            {
+                trace!(
+                    "World ({},{}) converged after {step} steps with result: {result}, {cycle:?}",
+                    world_pos.row,
+                    world_pos.col
                 );
                 if step.is_even() {
                     finished_worlds.insert(*world_pos, (result, world.last_result));
@@ -305,7 +269,7 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
             let new_world = World {
                 current_positions: next_positions,
                 last_result: result,
-                last_last_result: world.last_result,
+                detector: mem::take(&mut world.detector),
             };
             new_worlds.insert(*world_pos, new_world);
         }
@@ -313,42 +277,30 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
             if finished_worlds.contains_key(&next_other_world_pos) {
                 continue;
             }
-            if new_worlds.contains_key(&next_other_world_pos) {
-                new_worlds
-                    .get_mut(&next_other_world_pos)
-                    .unwrap()
-                    .current_positions
-                    .extend(other_world_positions);
-            } else {
-                let new_result = other_world_positions.len();
-                let new_world = World {
-                    current_positions: other_world_positions,
-                    last_result: new_result,
-                    last_last_result: 0,
-                };
-                new_worlds.insert(next_other_world_pos, new_world);
+            match new_worlds.entry(next_other_world_pos) {
+                Entry::Occupied(mut entry) => {
+                    entry
+                        .get_mut()
+                        .current_positions
+                        .extend(other_world_positions);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(World::new(other_world_positions));
+                }
             }
         }
 
         worlds = new_worlds;
-        if finish_cycle > 1 {
-            if step == finish_cycle {
-                let (result, (even, odd)) = get_result(&worlds, &finished_worlds, step.is_even());
-                println!(
-                    "Step {step} current result 1 finished world: {result}, One world: even: {even}, odd: {odd}"
-                );
-            }
-            if step == 2 * finish_cycle {
-                let (result, (even, odd)) = get_result(&worlds, &finished_worlds, step.is_even());
-                println!(
-                    "Step {step} current result 5 finished world: {result}, One world: even: {even}, odd: {odd}"
-                );
-            }
-            if step == 3 * finish_cycle {
-                let (result, (even, odd)) = get_result(&worlds, &finished_worlds, step.is_even());
-                println!(
-                    "Step {step} current result 13 finished world: {result}, One world: even: {even}, odd: {odd}"
-                );
+        // The intermediate results are only computed for the log.
+        if finish_cycle > 1 && log::enabled(Level::Debug) {
+            for (multiple, finished) in [(1, 1), (2, 5), (3, 13)] {
+                if step == multiple * finish_cycle {
+                    let (result, (even, odd)) =
+                        get_result(&worlds, &finished_worlds, step.is_even());
+                    debug!(
+                        "Step {step} current result {finished} finished world: {result}, One world: even: {even}, odd: {odd}"
+                    );
+                }
             }
         }
     }
@@ -360,43 +312,43 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
             last_step,
             (num_worlds_similar_to_start, num_worlds_different_to_start),
         ) = calculate_multiple_worlds(multiple);
-        println!("multiple: {multiple}, possibly_finished_worlds : {multiple_finished_worlds}, world similarity: ({num_worlds_similar_to_start}, {num_worlds_different_to_start})");
+        debug!("multiple: {multiple}, possibly_finished_worlds : {multiple_finished_worlds}, world similarity: ({num_worlds_similar_to_start}, {num_worlds_different_to_start})");
         let corner_multiple = last_step / 4;
-        println!("corner_multiple: {corner_multiple}");
+        debug!("corner_multiple: {corner_multiple}");
         let diag_corner_multiple = corner_multiple + 1;
-        println!("diag_corner_multiple: {diag_corner_multiple}");
+        debug!("diag_corner_multiple: {diag_corner_multiple}");
 
         let (_, start_world_even_odd_behaviour) =
             get_result(&worlds, &finished_worlds, steps.is_even());
-        println!(
+        debug!(
             "start_world_even_odd_behaviour: ({}, {})",
             start_world_even_odd_behaviour.0, start_world_even_odd_behaviour.1
         );
-        let left = worlds[&(0, -2)].last_result; //5678;
-        let right = worlds[&(0, 2)].last_result; //5674;
-        let up = worlds[&(-2, 0)].last_result; //5678;
-        let down = worlds[&(2, 0)].last_result; //5674;
-        let top_left = worlds[&(-1, -1)].last_result; //6611;
-        let top_right = worlds[&(-1, 1)].last_result; //6587;
-        let bottom_left = worlds[&(1, -1)].last_result; //6587;
-        let bottom_right = worlds[&(1, 1)].last_result; //6611;
-
-        let bottom_right_right = worlds[&(1, 2)].last_result; //965;
-        let bottom_right_left = worlds[&(2, 1)].last_result; //965;
-        println!("{bottom_right_left} == {bottom_right_right}");
+        let left = worlds[&Point::new(0, -2)].last_result; //5678;
+        let right = worlds[&Point::new(0, 2)].last_result; //5674;
+        let up = worlds[&Point::new(-2, 0)].last_result; //5678;
+        let down = worlds[&Point::new(2, 0)].last_result; //5674;
+        let top_left = worlds[&Point::new(-1, -1)].last_result; //6611;
+        let top_right = worlds[&Point::new(-1, 1)].last_result; //6587;
+        let bottom_left = worlds[&Point::new(1, -1)].last_result; //6587;
+        let bottom_right = worlds[&Point::new(1, 1)].last_result; //6611;
+
+        let bottom_right_right = worlds[&Point::new(1, 2)].last_result; //965;
+        let bottom_right_left = worlds[&Point::new(2, 1)].last_result; //965;
+        trace!("{bottom_right_left} == {bottom_right_right}");
         assert_eq!(bottom_right_left, bottom_right_right);
-        let bottom_left_right = worlds[&(2, -1)].last_result; //950;
-        let bottom_left_left = worlds[&(1, -2)].last_result; //950;
-        println!("{bottom_left_left} == {bottom_left_right}");
+        let bottom_left_right = worlds[&Point::new(2, -1)].last_result; //950;
+        let bottom_left_left = worlds[&Point::new(1, -2)].last_result; //950;
+        trace!("{bottom_left_left} == {bottom_left_right}");
         assert_eq!(bottom_left_left, bottom_left_right);
-        let top_right_right = worlds[&(-1, 2)].last_result; //948;
-        let top_right_left = worlds[&(-2, 1)].last_result; //948;
-        println!("{top_right_left} == {top_right_right}");
+        let top_right_right = worlds[&Point::new(-1, 2)].last_result; //948;
+        let top_right_left = worlds[&Point::new(-2, 1)].last_result; //948;
+        trace!("{top_right_left} == {top_right_right}");
         assert_eq!(top_right_left, top_right_right);
 
-        let top_left_right = worlds[&(-2, -1)].last_result; //943;
-        let top_left_left = worlds[&(-1, -2)].last_result; //943;
-        println!("{top_left_left} == {top_left_right}");
+        let top_left_right = worlds[&Point::new(-2, -1)].last_result; //943;
+        let top_left_left = worlds[&Point::new(-1, -2)].last_result; //943;
+        trace!("{top_left_left} == {top_left_right}");
         assert_eq!(top_left_left, top_left_right);
 
         // println!(
@@ -424,7 +376,7 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
             finished_world_result +=
                 num_worlds_different_to_start * start_world_even_odd_behaviour.0;
         }
-        println!("Steps: {steps}, finished_world_result: {finished_world_result}");
+        debug!("Steps: {steps}, finished_world_result: {finished_world_result}");
         return finished_world_result
             + left
             + right
@@ -442,101 +394,112 @@ fn solve_part_two(input: &Vec<Vec<char>>, steps: usize, finish_cycle: usize) ->
     get_result(&worlds, &finished_worlds, steps.is_even()).0
 }
 
-fn get_input(file: &str) -> Vec<Vec<char>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.chars().collect())
-        .collect()
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    input::expect_single(Day21::DAY, input, 'S', "start 'S'")?;
+    input::parse_grid(Day21::DAY, input, ".#S")
+}
+
+pub fn get_input(file: &str) -> Result<Grid<char>, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day21/input.txt");
-    let sum_part_one = solve_part_one(&input, 64);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input, 26501365, 131);
-    println!("Part 2: {sum_part_two}");
+pub struct Day21;
+
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input, 64).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input, 26501365, 131).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day21_example_input_part_one() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input, 6);
         assert_eq!(16, sum_part_one);
     }
 
     #[test]
     fn day21_input_part_one() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input, 64);
         assert_eq!(3649, sum_part_one);
     }
 
     #[test]
     fn day21_input_part_one_500_steps() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input, 500);
         assert_eq!(7457, sum_part_one);
     }
 
     #[test]
     fn day21_example_input_part_two_6_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 6, 1);
         assert_eq!(16, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_10_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 10, 1);
         assert_eq!(50, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_50_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 50, 1);
         assert_eq!(1594, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_100_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 100, 1);
         assert_eq!(6536, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_500_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 500, 1);
         assert_eq!(167004, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_1000_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 1000, 1);
         assert_eq!(668697, sum_part_two);
     }
 
     #[test]
     fn day21_example_input_part_two_5000_steps() {
-        let input = get_input("./src/day21/example_input.txt");
+        let input = get_input("./src/day21/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 5000, 1);
         assert_eq!(16733044, sum_part_two);
     }
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn day21_input_part_two_custom_steps_2() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 2 * 131 + 65, 131);
         assert_eq!(
             (1 * 7520 + 4 * 7457)
@@ -558,7 +521,7 @@ mod tests {
 
     #[test]
     fn day21_input_part_two_custom_steps_2_equality() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 2 * 131 + 65, 1);
         let sum_part_two_extrapolate = solve_part_two(&input, 2 * 131 + 65, 131);
         assert_eq!(sum_part_two, sum_part_two_extrapolate);
@@ -566,7 +529,7 @@ mod tests {
 
     #[test]
     fn day21_input_part_two_custom_steps_3() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 3 * 131 + 65, 131);
         assert_eq!(
             (9 * 7457 + 4 * 7520)
@@ -588,7 +551,7 @@ mod tests {
 
     #[test]
     fn day21_input_part_two_custom_steps_3_equality() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 3 * 131 + 65, 1);
         let sum_part_two_extrapolate = solve_part_two(&input, 3 * 131 + 65, 131);
         assert_eq!(sum_part_two, sum_part_two_extrapolate);
@@ -596,7 +559,7 @@ mod tests {
 
     #[test]
     fn day21_input_part_two_custom_steps_4_equality() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 4 * 131 + 65, 1);
         let sum_part_two_extrapolate = solve_part_two(&input, 4 * 131 + 65, 131);
         assert_eq!(sum_part_two, sum_part_two_extrapolate);
@@ -604,27 +567,29 @@ mod tests {
 
     #[test]
     fn day21_input_part_two_fraction() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 202300 * 131 + 65, 131);
         assert_eq!(612941134797232, sum_part_two);
     }
 
     #[test]
     fn day21_input_part_two() {
-        let input = get_input("./src/day21/input.txt");
+        let input = get_input("./src/day21/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input, 26501365, 131);
         assert_eq!(612941134797232, sum_part_two);
     }
 
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day21/input.txt");
-        b.iter(|| solve_part_one(&input, 64))
+    #[test]
+    fn day21_parse_needs_a_single_start() {
+        assert_eq!(
+            "day 21, line 2, column 4: expected a start 'S', found end of line",
+            parse("...\n...").unwrap_err().to_string()
+        );
+        assert_eq!(
+            (1, 3),
+            parse(".SS\n...")
+                .map_err(|e| (e.line, e.column))
+                .unwrap_err()
+        );
     }
-
-    // #[bench]
-    // fn bench_part_two(b: &mut Bencher) {
-    //     let input = get_input("./src/day21/input.txt");
-    //     b.iter(|| solve_part_two(&input))
-    // }
 }
@@ -1,35 +1,21 @@
-use std::{cmp::max, collections::HashMap, fs};
+//! Day 22: Sand Slabs.
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
-struct Block {
-    start: [usize; 3],
-    end: [usize; 3],
-}
+use std::{cmp::max, collections::HashMap};
 
-fn parse_input(input: &Vec<String>) -> Vec<Block> {
-    input
-        .iter()
-        .map(|line| {
-            let split = line.split("~").collect::<Vec<&str>>();
-            Block {
-                start: split[0]
-                    .split(",")
-                    .map(|s| s.parse().unwrap())
-                    .collect::<Vec<usize>>()
-                    .try_into()
-                    .unwrap(),
-                end: split[1]
-                    .split(",")
-                    .map(|s| s.parse().unwrap())
-                    .collect::<Vec<usize>>()
-                    .try_into()
-                    .unwrap(),
-            }
-        })
-        .collect()
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+/// A brick from its `start` to its `end` cube as `[x, y, z]`, both included.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Block {
+    pub start: [usize; 3],
+    pub end: [usize; 3],
 }
 
-fn sort_blocks(blocks: &Vec<Block>) -> Vec<Block> {
+/// The blocks ordered by their lowest cube, the order [`settle_blocks`] expects.
+pub fn sort_blocks(blocks: &Vec<Block>) -> Vec<Block> {
     let mut sorted_blocks = blocks.clone();
     sorted_blocks.sort_unstable_by_key(|block| block.start[2]);
     sorted_blocks
@@ -55,7 +41,13 @@ fn get_terrain_height(height_map: &HashMap<(usize, usize), usize>, block: &Block
     height
 }
 
-fn settle_blocks(blocks: &Vec<Block>) -> (Vec<Block>, usize) {
+/// Lets the `blocks`, sorted by their lowest cube, fall until they rest on the ground or another
+/// block. Returns the settled blocks and how many of them fell, `on_settled` sees the blocks settled
+/// so far after every block.
+pub fn settle_blocks(
+    blocks: &Vec<Block>,
+    on_settled: &mut dyn FnMut(&[Block]),
+) -> (Vec<Block>, usize) {
     let mut settled_blocks: Vec<Block> = Vec::new();
     let mut height_map: HashMap<(usize, usize), usize> = HashMap::new();
     let mut num_fallen_blocks = 0;
@@ -63,6 +55,7 @@ fn settle_blocks(blocks: &Vec<Block>) -> (Vec<Block>, usize) {
         if block.start[2] == 1 {
             update_height_map(&mut height_map, block);
             settled_blocks.push(block.clone());
+            on_settled(&settled_blocks);
             return;
         }
         let height = get_terrain_height(&height_map, block);
@@ -70,6 +63,7 @@ fn settle_blocks(blocks: &Vec<Block>) -> (Vec<Block>, usize) {
         if block.start[2] == target_height {
             update_height_map(&mut height_map, block);
             settled_blocks.push(block.clone());
+            on_settled(&settled_blocks);
             return;
         }
         if block.start[2] > target_height {
@@ -80,6 +74,7 @@ fn settle_blocks(blocks: &Vec<Block>) -> (Vec<Block>, usize) {
             update_height_map(&mut height_map, &settled_block);
             settled_blocks.push(settled_block);
             num_fallen_blocks += 1;
+            on_settled(&settled_blocks);
             return;
         }
         panic!("New Special case detected");
@@ -88,15 +83,14 @@ fn settle_blocks(blocks: &Vec<Block>) -> (Vec<Block>, usize) {
     (settled_blocks, num_fallen_blocks)
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
-    let blocks = parse_input(&input);
-    let sorted_blocks = sort_blocks(&blocks);
-    let (settled_blocks, _) = settle_blocks(&sorted_blocks);
+pub fn solve_part_one(blocks: &Vec<Block>) -> usize {
+    let sorted_blocks = sort_blocks(blocks);
+    let (settled_blocks, _) = settle_blocks(&sorted_blocks, &mut |_| {});
     let mut disintegrateable_blocks = 0;
     for i in 0..settled_blocks.len() {
         let mut blocks_after_disintegration = settled_blocks.clone();
         blocks_after_disintegration.remove(i);
-        let (_, num_fallen_blocks) = settle_blocks(&blocks_after_disintegration);
+        let (_, num_fallen_blocks) = settle_blocks(&blocks_after_disintegration, &mut |_| {});
         if num_fallen_blocks == 0 {
             disintegrateable_blocks += 1;
         }
@@ -105,81 +99,93 @@ fn solve_part_one(input: &Vec<String>) -> usize {
     disintegrateable_blocks
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
-    let _ = parse_input(&input);
-    let blocks = parse_input(&input);
-    let sorted_blocks = sort_blocks(&blocks);
-    let (settled_blocks, _) = settle_blocks(&sorted_blocks);
+pub fn solve_part_two(blocks: &Vec<Block>) -> usize {
+    let sorted_blocks = sort_blocks(blocks);
+    let (settled_blocks, _) = settle_blocks(&sorted_blocks, &mut |_| {});
     let mut sum_fallen_bricks = 0;
     for i in 0..settled_blocks.len() {
         let mut blocks_after_disintegration = settled_blocks.clone();
         blocks_after_disintegration.remove(i);
-        let (_, num_fallen_blocks) = settle_blocks(&blocks_after_disintegration);
+        let (_, num_fallen_blocks) = settle_blocks(&blocks_after_disintegration, &mut |_| {});
         sum_fallen_bricks += num_fallen_blocks
     }
 
     sum_fallen_bricks
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect()
+fn parse_position(line: Line, position: &str) -> Result<[usize; 3], ParseError> {
+    let mut coordinates = position.split(',');
+    let mut next = || {
+        let coordinate = coordinates.next().unwrap_or(&position[position.len()..]);
+        line.parse::<usize>(coordinate, "a coordinate")
+    };
+    Ok([next()?, next()?, next()?])
+}
+
+fn parse_block(line: Line) -> Result<Block, ParseError> {
+    let (start, end) = line.split_once(line.text(), "~")?;
+    Ok(Block {
+        start: parse_position(line, start)?,
+        end: parse_position(line, end)?,
+    })
+}
+
+pub fn parse(input: &str) -> Result<Vec<Block>, ParseError> {
+    input::parse_lines(Day22::DAY, input, parse_block)
+}
+
+pub fn get_input(file: &str) -> Result<Vec<Block>, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day22/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+    type Input = Vec<Block>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day22_example_input_part_one() {
-        let input = get_input("./src/day22/example_input.txt");
+        let input = get_input("./src/day22/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(5, sum_part_one);
     }
 
     #[test]
     fn day22_input_part_one() {
-        let input = get_input("./src/day22/input.txt");
+        let input = get_input("./src/day22/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(509, sum_part_one);
     }
 
     #[test]
     fn day22_example_input_part_two() {
-        let input = get_input("./src/day22/example_input.txt");
+        let input = get_input("./src/day22/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(7, sum_part_two);
     }
 
     #[test]
     fn day22_input_part_two() {
-        let input = get_input("./src/day22/input.txt");
+        let input = get_input("./src/day22/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(102770, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day22/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day22/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
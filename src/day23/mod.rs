@@ -1,27 +1,31 @@
-use std::{collections::HashMap, fs};
+//! Day 23: A Long Walk.
+//!
+//! Part two ignores the slopes and searches the longest path on the graph of junctions.
 
-// fn print_layout_with_route(layout: &Vec<Vec<char>>, route: &Vec<(i128, i128)>){
-//     let mut print_layout = layout.to_vec();
-//     for tile in route {
-//         print_layout[tile.0 as usize][tile.1 as usize] = '0';
-//     }
-//     println!("{:?}", print_layout);
-// }
+use std::collections::HashMap;
 
-fn get_successors(
-    layout: &Vec<Vec<char>>,
-    route: &Vec<(i128, i128)>,
-    pos: &(i128, i128),
-) -> Vec<Vec<(i128, i128)>> {
-    let possible_next_positions = vec![
-        (pos.0 + 1, pos.1),
-        (pos.0, pos.1 + 1),
-        (pos.0 - 1, pos.1),
-        (pos.0, pos.1 - 1),
-    ];
-    let mut get_successors: Vec<Vec<(i128, i128)>> = Vec::new();
-    for next_position in possible_next_positions {
-        let terrain = try_get_value(layout, &next_position);
+use crate::{
+    geometry::{Dir, Point},
+    grid::Grid,
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+fn slope_direction(tile: char) -> Option<Dir> {
+    match tile {
+        '^' => Some(Dir::Up),
+        '>' => Some(Dir::Right),
+        'v' => Some(Dir::Down),
+        '<' => Some(Dir::Left),
+        _ => None,
+    }
+}
+
+fn get_successors(layout: &Grid<char>, route: &Vec<Point>, pos: &Point) -> Vec<Vec<Point>> {
+    let mut get_successors: Vec<Vec<Point>> = Vec::new();
+    for dir in Dir::ALL {
+        let next_position = *pos + dir;
+        let terrain = layout.try_get(next_position).copied();
         if terrain.is_none() {
             continue;
         }
@@ -34,50 +38,23 @@ fn get_successors(
         match terrain {
             '#' => continue,
             '.' => get_successors.push(vec![next_position]),
-            '>' => {
-                let slide_position = (next_position.0, next_position.1 + 1);
-                if !route.contains(&slide_position) {
-                    get_successors.push(vec![next_position, slide_position])
-                }
-            }
-            'v' => {
-                let slide_position = (next_position.0 + 1, next_position.1);
-                if !route.contains(&slide_position) {
-                    get_successors.push(vec![next_position, slide_position])
-                }
-            }
-            '<' => {
-                let slide_position = (next_position.0, next_position.1 - 1);
+            _ => {
+                let slope = slope_direction(terrain).expect("Unexpected Tile found");
+                let slide_position = next_position + slope;
                 if !route.contains(&slide_position) {
                     get_successors.push(vec![next_position, slide_position])
                 }
             }
-            '^' => {
-                let slide_position = (next_position.0 - 1, next_position.1);
-                if !route.contains(&slide_position) {
-                    get_successors.push(vec![next_position, slide_position])
-                }
-            }
-            _ => panic!("Unexpected Tile found"),
         }
     }
     get_successors
 }
 
-fn get_successors_part_two(
-    layout: &Vec<Vec<char>>,
-    route: &Vec<(i128, i128)>,
-    pos: &(i128, i128),
-) -> Vec<(i128, i128)> {
-    let possible_next_positions = vec![
-        (pos.0 + 1, pos.1),
-        (pos.0, pos.1 + 1),
-        (pos.0 - 1, pos.1),
-        (pos.0, pos.1 - 1),
-    ];
-    let mut get_successors: Vec<(i128, i128)> = Vec::new();
-    for next_position in possible_next_positions {
-        let terrain = try_get_value(layout, &next_position);
+fn get_successors_part_two(layout: &Grid<char>, route: &Vec<Point>, pos: &Point) -> Vec<Point> {
+    let mut get_successors: Vec<Point> = Vec::new();
+    for dir in Dir::ALL {
+        let next_position = *pos + dir;
+        let terrain = layout.try_get(next_position).copied();
         if terrain.is_none() {
             continue;
         }
@@ -96,33 +73,18 @@ fn get_successors_part_two(
     get_successors
 }
 
-fn try_get_value(layout: &Vec<Vec<char>>, position: &(i128, i128)) -> Option<char> {
-    if 0 <= position.0
-        && position.0 < layout.len() as i128
-        && 0 <= position.1
-        && position.1 < layout.first().unwrap().len() as i128
-    {
-        return Some(layout[position.0 as usize][position.1 as usize]);
-    }
-    return None;
-}
-
-fn step(
-    layout: &Vec<Vec<char>>,
-    route: &Vec<(i128, i128)>,
-    pos: &(i128, i128),
-    goal: &(i128, i128),
-) -> usize {
+// The longest route to `goal` that continues `route`, empty if there is none.
+fn step(layout: &Grid<char>, route: &Vec<Point>, pos: &Point, goal: &Point) -> Vec<Point> {
     let successors = get_successors(layout, route, pos);
-    let mut longest_route = 0;
+    let mut longest_route: Vec<Point> = Vec::new();
     for successor in &successors {
-        if successor.last().unwrap() == goal {
-            return route.len() + successor.len();
-        }
         let mut new_route = route.clone();
         new_route.extend(successor);
+        if successor.last().unwrap() == goal {
+            return new_route;
+        }
         let new_route = step(layout, &new_route, new_route.last().unwrap(), goal);
-        if new_route > longest_route {
+        if new_route.len() > longest_route.len() {
             longest_route = new_route;
         }
     }
@@ -130,23 +92,24 @@ fn step(
 }
 
 fn step_part_two(
-    crossings: &HashMap<(i128, i128), HashMap<(i128, i128), usize>>,
-    route: &Vec<(i128, i128)>,
-    goal: &(i128, i128),
-    steps_so_far: usize
+    crossings: &HashMap<Point, HashMap<Point, usize>>,
+    route: &Vec<Point>,
+    goal: &Point,
+    steps_so_far: usize,
 ) -> usize {
     let mut longest_route = 0;
     let current_pos = route.last().unwrap();
-    let mut new_route: Vec<(i128, i128)> = route.to_vec();
+    let mut new_route: Vec<Point> = route.to_vec();
     for successor in &crossings[current_pos] {
-        if route.contains(successor.0){
+        if route.contains(successor.0) {
             continue;
         }
         if successor.0 == goal {
             return steps_so_far + *successor.1;
         }
-        new_route.push(successor.0.clone());
-        let new_route_length = step_part_two(crossings, &new_route, goal, steps_so_far + *successor.1);
+        new_route.push(*successor.0);
+        let new_route_length =
+            step_part_two(crossings, &new_route, goal, steps_so_far + *successor.1);
         if new_route_length > longest_route {
             longest_route = new_route_length;
         }
@@ -155,73 +118,40 @@ fn step_part_two(
     longest_route
 }
 
-fn solve_part_one(layout: &Vec<Vec<char>>) -> usize {
-    let start: (i128, i128) = (
-        0,
-        layout
-            .first()
-            .unwrap()
-            .iter()
-            .position(|c| *c == '.')
-            .unwrap() as i128,
-    );
-    let goal: (i128, i128) = (
-        layout.len() as i128 - 1,
-        layout
-            .last()
-            .unwrap()
-            .iter()
-            .position(|c| *c == '.')
-            .unwrap() as i128,
-    );
-    let mut route: Vec<(i128, i128)> = Vec::new();
-    route.push(start);
-    let longest_route = step(layout, &route, &start, &goal);
-    longest_route - 1
+// The path tiles in the first and the last row.
+fn find_ends(layout: &Grid<char>) -> (Point, Point) {
+    let path_tile = |row: usize| {
+        let col = layout.row(row).iter().position(|c| *c == '.');
+        Point::new(row as i128, col.expect("parse checks the ends") as i128)
+    };
+    (path_tile(0), path_tile(layout.height() - 1))
 }
 
-fn solve_part_two(layout: &Vec<Vec<char>>) -> usize {
-    let mut clean_layout = layout.clone();
-    for row in 0..layout.len() {
-        for col in 0..layout[row].len() {
-            if layout[row][col] != '#' {
-                clean_layout[row][col] = '.'
-            }
-        }
-    }
-    let layout = clean_layout;
+/// The tiles of the longest hike from the start to the goal when slopes can only be walked down.
+pub fn find_longest_hike(layout: &Grid<char>) -> Vec<Point> {
+    let (start, goal) = find_ends(layout);
+    let route: Vec<Point> = vec![start];
+    step(layout, &route, &start, &goal)
+}
 
-    let start: (i128, i128) = (
-        0,
-        layout
-            .first()
-            .unwrap()
-            .iter()
-            .position(|c| *c == '.')
-            .unwrap() as i128,
-    );
-    let goal: (i128, i128) = (
-        layout.len() as i128 - 1,
-        layout
-            .last()
-            .unwrap()
-            .iter()
-            .position(|c| *c == '.')
-            .unwrap() as i128,
-    );
-    let mut crossings: HashMap<(i128, i128), HashMap<(i128, i128), usize>> = HashMap::new(); // From crossing to other crossing with distance
-    for row in 0..layout.len() {
-        for col in 0..layout[row].len() {
-            if layout[row][col] != '.' {
+pub fn solve_part_one(layout: &Grid<char>) -> usize {
+    find_longest_hike(layout).len() - 1
+}
+
+pub fn solve_part_two(layout: &Grid<char>) -> usize {
+    let layout = layout.map(|tile| if *tile == '#' { '#' } else { '.' });
+
+    let (start, goal) = find_ends(&layout);
+    let mut crossings: HashMap<Point, HashMap<Point, usize>> = HashMap::new(); // From crossing to other crossing with distance
+    for row in 0..layout.height() {
+        for col in 0..layout.width() {
+            if layout[(row, col)] != '.' {
                 continue;
             }
-            let neighbours = get_successors_part_two(
-                &layout,
-                &vec![(row as i128, col as i128)],
-                &(row as i128, col as i128),
-            );
+            let pos = Point::from((row, col));
+            let neighbours = get_successors_part_two(&layout, &vec![pos], &pos);
             if neighbours.len() > 2 {
-                crossings.insert((row as i128, col as i128), HashMap::new());
+                crossings.insert(pos, HashMap::new());
             }
         }
     }
@@ -231,12 +161,15 @@ fn solve_part_two(layout: &Vec<Vec<char>>) -> usize {
     for (crossing_pos, _) in crossings {
         let neighbours = get_successors_part_two(&layout, &vec![crossing_pos], &crossing_pos);
         for neighbour in neighbours {
-            let mut route: Vec<(i128, i128)> = vec![crossing_pos, neighbour];
+            let mut route: Vec<Point> = vec![crossing_pos, neighbour];
             loop {
                 let next_positions =
                     get_successors_part_two(&layout, &route, route.last().unwrap());
                 if next_positions.len() != 1 {
-                    panic!("Invalid path found, next_positions.len(): {}", next_positions.len());
+                    panic!(
+                        "Invalid path found, next_positions.len(): {}",
+                        next_positions.len()
+                    );
                 }
                 let next_position = next_positions.first().unwrap();
                 if mapped_crossings.contains_key(next_position) {
@@ -255,70 +188,93 @@ fn solve_part_two(layout: &Vec<Vec<char>>) -> usize {
         }
     }
     let route = vec![start];
-    let longest_route = step_part_two(&mapped_crossings, &route, &goal, 0);
+    step_part_two(&mapped_crossings, &route, &goal, 0)
+}
 
-    longest_route
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    let layout = input::parse_grid(Day23::DAY, input, ".#^>v<")?;
+    let lines = input::lines(input);
+    let row = |number: usize| {
+        Line::new(
+            Day23::DAY,
+            number,
+            lines.get(number - 1).map_or("", |text| text.as_str()),
+        )
+    };
+    for (line, expected) in [
+        (row(1), "a path '.' in the first row"),
+        (row(lines.len().max(1)), "a path '.' in the last row"),
+    ] {
+        if !line.text().contains('.') {
+            return Err(line.error(line.text(), expected));
+        }
+    }
+    Ok(layout)
 }
 
-fn get_input(file: &str) -> Vec<Vec<char>> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.chars().collect::<Vec<char>>())
-        .collect()
+pub fn get_input(file: &str) -> Result<Grid<char>, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day23/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+    type Input = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day23_example_input_part_one() {
-        let input = get_input("./src/day23/example_input.txt");
+        let input = get_input("./src/day23/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(94, sum_part_one);
     }
 
     #[test]
     fn day23_input_part_one() {
-        let input = get_input("./src/day23/input.txt");
+        let input = get_input("./src/day23/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(2042, sum_part_one);
     }
 
     #[test]
     fn day23_example_input_part_two() {
-        let input = get_input("./src/day23/example_input.txt");
+        let input = get_input("./src/day23/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(154, sum_part_two);
     }
 
     #[test]
     fn day23_input_part_two() {
-        let input = get_input("./src/day23/input.txt");
+        let input = get_input("./src/day23/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(6466, sum_part_two);
     }
 
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day23/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day23/input.txt");
-        b.iter(|| solve_part_two(&input))
+    #[test]
+    fn day23_parse_needs_an_entry_and_an_exit() {
+        let error = parse("#.#\n#.#\n###").unwrap_err();
+        assert_eq!(
+            "day 23, line 3, column 1: expected a path '.' in the last row, found '###'",
+            error.to_string()
+        );
+        assert_eq!(1, parse("###\n#.#").unwrap_err().line);
+        assert!(parse("").is_err());
     }
 }
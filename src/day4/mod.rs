@@ -1,34 +1,22 @@
-use std::{cmp::min, collections::HashSet, fs};
+//! Day 4: Scratchcards.
 
-fn get_all_wins(lines: &Vec<String>) -> Vec<usize> {
-    lines
+use std::{cmp::min, collections::HashSet};
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+/// The winning numbers of a scratchcard and the numbers on it.
+pub type Card = (HashSet<u32>, HashSet<u32>);
+
+fn get_all_wins(cards: &Vec<Card>) -> Vec<usize> {
+    cards
         .iter()
-        .map(|line| {
-            let split: Vec<String> = line
-                .split(':')
-                .last()
-                .unwrap()
-                .trim()
-                .split('|')
-                .map(|s| s.to_owned())
-                .collect();
-            let winning_numbers: HashSet<u32> = split
-                .first()
-                .unwrap()
-                .trim()
-                .split_whitespace()
-                .map(|s| s.parse::<u32>().unwrap())
-                .collect();
-            let my_numbers: HashSet<u32> = split
-                .last()
-                .unwrap()
-                .trim()
-                .split_whitespace()
-                .map(|s| s.parse::<u32>().unwrap())
-                .collect();
+        .map(|(winning_numbers, my_numbers)| {
             let number_of_wins: usize = winning_numbers
-                .intersection(&my_numbers)
-                .map(|n| *n)
+                .intersection(my_numbers)
+                .copied()
                 .collect::<Vec<u32>>()
                 .len();
 
@@ -37,8 +25,8 @@ fn get_all_wins(lines: &Vec<String>) -> Vec<usize> {
         .collect()
 }
 
-fn solve_part_one(lines: &Vec<String>) -> usize {
-    let all_wins = get_all_wins(lines);
+pub fn solve_part_one(cards: &Vec<Card>) -> usize {
+    let all_wins = get_all_wins(cards);
     all_wins
         .iter()
         .map(|number_of_wins| {
@@ -51,8 +39,8 @@ fn solve_part_one(lines: &Vec<String>) -> usize {
         .sum()
 }
 
-fn solve_part_two(lines: &Vec<String>) -> usize {
-    let all_wins = get_all_wins(lines);
+pub fn solve_part_two(cards: &Vec<Card>) -> usize {
+    let all_wins = get_all_wins(cards);
     let mut scratchcards: Vec<usize> = vec![1; all_wins.len()];
     for i in 0..(all_wins.len() - 1) {
         let number_of_wins = all_wins[i];
@@ -60,29 +48,55 @@ fn solve_part_two(lines: &Vec<String>) -> usize {
             continue;
         }
         let current_cards = scratchcards[i];
-        for j in (i + 1)..min(i + 1 + number_of_wins , all_wins.len()) {
+        for j in (i + 1)..min(i + 1 + number_of_wins, all_wins.len()) {
             scratchcards[j] += current_cards;
         }
     }
     scratchcards.iter().sum()
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let lines: Vec<String> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return lines;
+fn parse_numbers(line: Line, numbers: &str) -> Result<HashSet<u32>, ParseError> {
+    numbers
+        .split_whitespace()
+        .map(|s| line.parse::<u32>(s, "a number"))
+        .collect()
 }
 
-pub fn solver() {
-    let input = get_input("./src/day4/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 1: {sum_part_one}");
-    println!("Part 2: {sum_part_two}");
+// winning numbers, my numbers
+fn parse_card(line: Line) -> Result<Card, ParseError> {
+    let (_, numbers) = line.split_once(line.text(), ":")?;
+    let (winning_numbers, my_numbers) = line.split_once(numbers, "|")?;
+    Ok((
+        parse_numbers(line, winning_numbers)?,
+        parse_numbers(line, my_numbers)?,
+    ))
+}
+
+pub fn parse(input: &str) -> Result<Vec<Card>, ParseError> {
+    input::parse_lines(Day4::DAY, input, parse_card)
+}
+
+pub fn get_input(file: &str) -> Result<Vec<Card>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
@@ -91,28 +105,28 @@ mod tests {
 
     #[test]
     fn day4_example_input_part_one() {
-        let input = get_input("./src/day4/example_input.txt");
+        let input = get_input("./src/day4/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(13, sum_part_one);
     }
 
     #[test]
     fn day4_input_part_one() {
-        let input = get_input("./src/day4/input.txt");
+        let input = get_input("./src/day4/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(21485, sum_part_one);
     }
 
     #[test]
     fn day4_example_input_part_two() {
-        let input = get_input("./src/day4/example_input.txt");
+        let input = get_input("./src/day4/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(30, sum_part_two);
     }
 
     #[test]
     fn day4_input_part_two() {
-        let input = get_input("./src/day4/input.txt");
+        let input = get_input("./src/day4/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(11024379, sum_part_two);
     }
@@ -1,34 +1,16 @@
-use std::{cmp::min, fs};
+//! Day 5: If You Give A Seed A Fertilizer.
+//!
+//! Part two walks the seed ranges and skips ahead as far as the current mapping ranges allow.
 
-fn get_seed_and_maps(groups: &Vec<String>) -> (Vec<u128>, Vec<Vec<[u128; 3]>>) {
-    let seeds: Vec<u128> = groups
-        .first()
-        .unwrap()
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse::<u128>().unwrap())
-        .collect();
-
-    let maps: Vec<Vec<[u128; 3]>> = groups
-        .iter()
-        .skip(1)
-        .map(|group| {
-            group
-                .split("\r\n")
-                .skip(1)
-                .map(|line| {
-                    let vec = line
-                        .split_whitespace()
-                        .map(|s| s.parse::<u128>().unwrap())
-                        .into_iter()
-                        .collect::<Vec<u128>>();
-                    [vec[0], vec[1], vec[2]]
-                })
-                .collect()
-        })
-        .collect();
-    return (seeds, maps);
-}
+use std::cmp::min;
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+/// The seeds and, for every map of the almanac, its `[destination, source, length]` ranges.
+pub type Almanac = (Vec<u128>, Vec<Vec<[u128; 3]>>);
 
 fn get_location_and_skip(seed: u128, maps: &Vec<Vec<[u128; 3]>>) -> (u128, u128) {
     let mut value = seed;
@@ -49,11 +31,10 @@ fn get_location_and_skip(seed: u128, maps: &Vec<Vec<[u128; 3]>>) -> (u128, u128)
     (value, skip)
 }
 
-fn solve_part_one(groups: &Vec<String>) -> u128 {
+pub fn solve_part_one((seeds, maps): &Almanac) -> u128 {
     let mut min_location = u128::MAX;
-    let (seeds, maps) = get_seed_and_maps(groups);
     for seed in seeds {
-        let (location, _) = get_location_and_skip(seed, &maps);
+        let (location, _) = get_location_and_skip(*seed, maps);
         if location < min_location {
             min_location = location;
         }
@@ -61,18 +42,18 @@ fn solve_part_one(groups: &Vec<String>) -> u128 {
     min_location
 }
 
-fn solve_part_two(groups: &Vec<String>) -> u128 {
+pub fn solve_part_two((seeds, maps): &Almanac) -> u128 {
     let mut min_location = u128::MAX;
-    let (seed_range, maps) = get_seed_and_maps(groups);
+    let seed_range = seeds;
 
     for i in (0..seed_range.len()).step_by(2) {
         let mut seed = seed_range[i];
         while seed < seed_range[i] + seed_range[i + 1] {
-            let (location, skip) = get_location_and_skip(seed, &maps);
+            let (location, skip) = get_location_and_skip(seed, maps);
             if location < min_location {
                 min_location = location;
             }
-            if skip == u128::MAX{
+            if skip == u128::MAX {
                 break;
             }
             seed += skip;
@@ -81,66 +62,94 @@ fn solve_part_two(groups: &Vec<String>) -> u128 {
     min_location
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let groups: Vec<String> = input
-        .split("\r\n\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return groups;
+fn parse_seeds(line: Line) -> Result<Vec<u128>, ParseError> {
+    let (_, seeds) = line.split_once(line.text(), ":")?;
+    seeds
+        .split_whitespace()
+        .map(|s| line.parse::<u128>(s, "a seed number"))
+        .collect()
+}
+
+// destination start, source start, length
+fn parse_mapping(line: Line) -> Result<[u128; 3], ParseError> {
+    let mut numbers = line.text().split_whitespace();
+    let mut next = || line.parse::<u128>(line.next(&mut numbers, "a number")?, "a number");
+    Ok([next()?, next()?, next()?])
+}
+
+pub fn parse(input: &str) -> Result<Almanac, ParseError> {
+    // The first group holds the seeds, every other group a map below its header.
+    let mut seeds: Option<Vec<u128>> = None;
+    let maps = input::parse_groups(Day5::DAY, input, |group| {
+        if seeds.is_none() {
+            seeds = Some(parse_seeds(group[0])?);
+            return Ok(None);
+        }
+        group
+            .iter()
+            .skip(1)
+            .map(|line| parse_mapping(*line))
+            .collect::<Result<Vec<[u128; 3]>, ParseError>>()
+            .map(Some)
+    })?;
+    Ok((
+        seeds.unwrap_or_default(),
+        maps.into_iter().flatten().collect(),
+    ))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day5/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Almanac, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+    type Input = Almanac;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day5_example_input_part_one() {
-        let input = get_input("./src/day5/example_input.txt");
+        let input = get_input("./src/day5/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(35, sum_part_one);
     }
 
     #[test]
     fn day5_input_part_one() {
-        let input = get_input("./src/day5/input.txt");
+        let input = get_input("./src/day5/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(111627841, sum_part_one);
     }
 
     #[test]
     fn day5_example_input_part_two() {
-        let input = get_input("./src/day5/example_input.txt");
+        let input = get_input("./src/day5/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(46, sum_part_two);
     }
 
     #[test]
     fn day5_input_part_two() {
-        let input = get_input("./src/day5/input.txt");
+        let input = get_input("./src/day5/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(69323688, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day5/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day5/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
@@ -1,127 +1,131 @@
-use std::fs;
+//! Day 6: Wait For It.
+//!
+//! Part two reads the races as one race with the digits of all times and distances joined.
+//! Holding the button for `i` of `time` milliseconds goes `i * (time - i)`, the winning hold times
+//! lie between the roots of that quadratic and are counted with an integer square root.
 
-fn solve_part_one(input: &Vec<String>) -> usize {
-    let times: Vec<usize> = input[0]
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
-    let distances: Vec<usize> = input[1]
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
+use crate::{
+    input::{self, Line, ParseError},
+    math,
+    solution::Solution,
+};
+
+/// Race times and the distances to beat, one entry per race.
+pub type Races = (Vec<usize>, Vec<usize>);
+
+// The hold times that go further than the record distance.
+fn count_wins(time: u128, record_distance: u128) -> u128 {
+    let wins = |i: u128| i * (time - i) > record_distance;
+    let discriminant = match (time * time).checked_sub(4 * record_distance) {
+        Some(discriminant) if discriminant > 0 => discriminant,
+        _ => return 0,
+    };
+    // The rounded down root is at most one hold time short of the first win.
+    let mut first = (time - math::isqrt(discriminant)) / 2;
+    while !wins(first) {
+        if first >= time / 2 {
+            return 0;
+        }
+        first += 1;
+    }
+    time - 2 * first + 1
+}
+
+pub fn solve_part_one((times, distances): &Races) -> usize {
     let races: Vec<(&usize, &usize)> = times.iter().zip(distances.iter()).collect();
     let mut result: usize = 1;
     for (time, record_distance) in races {
-        let mut number_of_wins = 0;
-        for i in 1..*time {
-            let distance = i * (time - i);
-            if distance > *record_distance {
-                number_of_wins += 1;
-            }
-        }
-        result *= number_of_wins;
+        result *= count_wins(*time as u128, *record_distance as u128) as usize;
     }
 
     result
 }
 
-fn solve_part_two(input: &Vec<String>) -> u128 {
-    let time = input[0]
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.to_owned())
-        .reduce(|mut a, b| {
-            a.push_str(&b);
-            a
-        })
-        .unwrap()
+fn concat_numbers(numbers: &Vec<usize>) -> usize {
+    numbers
+        .iter()
+        .map(|number| number.to_string())
+        .collect::<String>()
         .parse::<usize>()
-        .unwrap();
-    let record_distance = input[1]
-        .split_whitespace()
-        .skip(1)
-        .map(|s| s.to_owned())
-        .reduce(|mut a, b| {
-            a.push_str(&b);
-            a
-        })
         .unwrap()
-        .parse::<usize>()
-        .unwrap();
+}
 
-    let mut number_of_wins = 0;
-    for i in 1..time {
-        let distance = i * (time - i);
-        if distance > record_distance {
-            number_of_wins += 1;
-        }
-    }
+pub fn solve_part_two((times, distances): &Races) -> u128 {
+    let time = concat_numbers(times);
+    let record_distance = concat_numbers(distances);
 
-    number_of_wins
+    count_wins(time as u128, record_distance as u128)
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let input: Vec<String> = input
-        .split("\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return input;
+fn parse_numbers(line: Line) -> Result<Vec<usize>, ParseError> {
+    let (_, numbers) = line.split_once(line.text(), ":")?;
+    numbers
+        .split_whitespace()
+        .map(|s| line.parse::<usize>(s, "a number"))
+        .collect()
+}
+
+// times, record distances
+pub fn parse(input: &str) -> Result<Races, ParseError> {
+    let mut lines = input::parse_lines(Day6::DAY, input, parse_numbers)?.into_iter();
+    Ok((
+        lines.next().unwrap_or_default(),
+        lines.next().unwrap_or_default(),
+    ))
+}
+
+pub fn get_input(file: &str) -> Result<Races, ParseError> {
+    parse(&input::read_file(file))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day6/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+    type Input = Races;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day6_example_input_part_one() {
-        let input = get_input("./src/day6/example_input.txt");
+        let input = get_input("./src/day6/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(288, sum_part_one);
     }
 
     #[test]
     fn day6_input_part_one() {
-        let input = get_input("./src/day6/input.txt");
+        let input = get_input("./src/day6/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(1159152, sum_part_one);
     }
 
     #[test]
     fn day6_example_input_part_two() {
-        let input = get_input("./src/day6/example_input.txt");
+        let input = get_input("./src/day6/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(71503, sum_part_two);
     }
 
     #[test]
     fn day6_input_part_two() {
-        let input = get_input("./src/day6/input.txt");
+        let input = get_input("./src/day6/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(41513103, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day6/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day6/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
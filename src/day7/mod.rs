@@ -1,4 +1,16 @@
-use std::{collections::HashSet, fs};
+//! Day 7: Camel Cards.
+//!
+//! Part two treats `J` as the weakest card that takes the place of whatever makes the best hand.
+
+use std::collections::HashSet;
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
+
+/// A hand of five cards and its bid.
+pub type Bid = (String, usize);
 
 #[derive(Clone, PartialOrd, PartialEq, Eq, Hash)]
 struct Hand {
@@ -61,15 +73,11 @@ fn sort_hands(hands: Vec<Hand>) -> Vec<Hand> {
     sorted_hands
 }
 
-fn parse_hands(input: &Vec<String>, part_two: bool) -> Vec<Hand> {
+fn parse_hands(input: &Vec<Bid>, part_two: bool) -> Vec<Hand> {
     input
         .iter()
-        .map(|line| {
-            let split = line
-                .split_whitespace()
-                .map(|s| s.to_owned())
-                .collect::<Vec<String>>();
-            let cards = split[0]
+        .map(|(labels, bid)| {
+            let cards = labels
                 .chars()
                 .map(|char| match char {
                     'A' => 14,
@@ -88,9 +96,9 @@ fn parse_hands(input: &Vec<String>, part_two: bool) -> Vec<Hand> {
                 .collect::<Vec<u32>>();
             Hand {
                 cards: cards.clone(),
-                bid: split[1].parse::<usize>().unwrap(),
+                bid: *bid,
                 hand_type: get_type_of_cards(&cards),
-                line: line.to_string(),
+                line: format!("{labels} {bid}"),
             }
         })
         .collect()
@@ -106,12 +114,12 @@ fn get_winnings(hands: Vec<Hand>) -> usize {
     sum
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
+pub fn solve_part_one(input: &Vec<Bid>) -> usize {
     let hands: Vec<Hand> = parse_hands(input, false);
     get_winnings(hands)
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
+pub fn solve_part_two(input: &Vec<Bid>) -> usize {
     let hands: Vec<Hand> = parse_hands(input, true);
     let optimized_hands = hands
         .iter()
@@ -120,66 +128,79 @@ fn solve_part_two(input: &Vec<String>) -> usize {
     get_winnings(optimized_hands)
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let groups: Vec<String> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return groups;
+// cards, bid
+fn parse_hand(line: Line) -> Result<Bid, ParseError> {
+    let mut split = line.text().split_whitespace();
+    let cards = line.next(&mut split, "five cards")?;
+    if let Some((offset, _)) = cards
+        .char_indices()
+        .find(|(_, c)| !"AKQJT98765432".contains(*c))
+    {
+        return Err(line.error(&cards[offset..offset + 1], "a card A, K, Q, J, T or 2-9"));
+    }
+    if cards.len() != 5 {
+        return Err(line.error(cards, "five cards"));
+    }
+    let bid = line.parse::<usize>(line.next(&mut split, "a bid")?, "a bid")?;
+    Ok((cards.to_owned(), bid))
+}
+
+pub fn parse(input: &str) -> Result<Vec<Bid>, ParseError> {
+    input::parse_lines(Day7::DAY, input, parse_hand)
 }
 
-pub fn solver() {
-    let input = get_input("./src/day7/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Vec<Bid>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+    type Input = Vec<Bid>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve_part_two(input).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day7_example_input_part_one() {
-        let input = get_input("./src/day7/example_input.txt");
+        let input = get_input("./src/day7/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(6440, sum_part_one);
     }
 
     #[test]
     fn day7_input_part_one() {
-        let input = get_input("./src/day7/input.txt");
+        let input = get_input("./src/day7/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(241344943, sum_part_one);
     }
 
     #[test]
     fn day7_example_input_part_two() {
-        let input = get_input("./src/day7/example_input.txt");
+        let input = get_input("./src/day7/example_input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(5905, sum_part_two);
     }
 
     #[test]
     fn day7_input_part_two() {
-        let input = get_input("./src/day7/input.txt");
+        let input = get_input("./src/day7/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
         assert_eq!(243101568, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day7/input.txt");
-        b.iter(|| solve_part_one(&input))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day7/input.txt");
-        b.iter(|| solve_part_two(&input))
-    }
 }
@@ -1,32 +1,19 @@
-use std::{collections::HashMap, fs};
-use num::Integer;
-
-fn parse_commands(input: &Vec<String>) -> Vec<usize> {
-    let commands: Vec<usize> = input[0]
-        .chars()
-        .map(|char| match char {
-            'L' => 0,
-            'R' => 1,
-            _ => panic!("Command does not match."),
-        })
-        .collect();
-    commands
-}
+//! Day 8: Haunted Wasteland.
+//!
+//! Part two finds the loop of every ghost walking from a node ending in `A` with
+//! [`cycle::brent`] and the first step at which all of them are on a node ending in `Z`.
 
-fn parse_map(input: &Vec<String>) -> HashMap<String, [String; 2]> {
-    let mut map: HashMap<String, [String; 2]> = HashMap::new();
-    input.iter().skip(2).for_each(|line| {
-        let split: Vec<String> = line.split(" = (").map(|s| s.trim().to_owned()).collect();
-        let from = split[0].clone();
-        let to: Vec<String> = (split[1][..(split[1].len() - 1)])
-            .to_owned()
-            .split(", ")
-            .map(|s| s.to_owned())
-            .collect();
-        map.insert(from, [to[0].clone(), to[1].clone()]);
-    });
-    map
-}
+use std::collections::HashMap;
+
+use crate::{
+    cycle::{self, Schedule},
+    input::{self, Line, ParseError},
+    solution::Solution,
+    warn,
+};
+
+/// The instructions as `0` for left and `1` for right, and the left and right node of every node.
+pub type Network = (Vec<usize>, HashMap<String, [String; 2]>);
 
 fn get_number_of_moves(
     commands: &Vec<usize>,
@@ -44,95 +31,152 @@ fn get_number_of_moves(
     moves
 }
 
-fn solve_part_one(input: &Vec<String>) -> usize {
-    let commands = parse_commands(input);
-    let map: HashMap<String, [String; 2]> = parse_map(input);
-
-    get_number_of_moves(&commands, &map, &"AAA".to_owned(), &"ZZZ".to_owned())
+pub fn solve_part_one((commands, map): &Network) -> usize {
+    get_number_of_moves(commands, map, &"AAA".to_owned(), &"ZZZ".to_owned())
 }
 
-fn solve_part_two(input: &Vec<String>) -> usize {
-    let commands = parse_commands(input);
-    let map: HashMap<String, [String; 2]> = parse_map(input);
+// The steps at which the ghost starting at `start_position` is on a node ending in `Z`. Its state
+// is the node and the position in the instructions.
+fn find_goal_schedule(
+    commands: &Vec<usize>,
+    map: &HashMap<String, [String; 2]>,
+    start_position: &String,
+) -> Schedule {
+    let step = |(position, moves): &(String, usize)| {
+        let command = commands[*moves];
+        (
+            map[position][command].to_owned(),
+            (moves + 1) % commands.len(),
+        )
+    };
+    let start = (start_position.to_owned(), 0);
+    let cycle = cycle::brent(&start, step);
+
+    let mut events: Vec<usize> = Vec::new();
+    let mut state = start;
+    for moves in 0..cycle.prefix + cycle.period {
+        if state.0.ends_with('Z') {
+            events.push(moves);
+        }
+        state = step(&state);
+    }
+    Schedule { cycle, events }
+}
 
-    let positions: Vec<&String> = map
+/// `None` if the ghosts are never on nodes ending in `Z` at once, or only after more than 2^64
+/// steps.
+pub fn solve_part_two((commands, map): &Network) -> Option<usize> {
+    let schedules: Vec<Schedule> = map
         .keys()
         .filter(|position| position.ends_with('A'))
+        .map(|position| find_goal_schedule(commands, map, position))
         .collect();
 
-    positions
-        .iter()
-        .map(|position| get_number_of_moves(&commands, &map, *position, &"Z".to_owned()))
-        .fold(1, |a, b| a.lcm(&b))
+    let moves = cycle::first_common_event(&schedules);
+    if moves.is_none() {
+        warn!("the ghosts are never on Z nodes at once, or only after more than 2^64 steps");
+    }
+    moves
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let lines: Vec<String> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return lines;
+fn parse_commands(line: Line) -> Result<Vec<usize>, ParseError> {
+    input::parse_row(line.text(), |command, _| match command {
+        "L" => Ok(0),
+        "R" => Ok(1),
+        _ => Err(line.error(command, "a command L or R")),
+    })
+}
+
+fn parse_node(line: Line) -> Result<(String, [String; 2]), ParseError> {
+    let (from, to) = line.split_once(line.text(), " = (")?;
+    let (to, _) = line.split_once(to, ")")?;
+    let (left, right) = line.split_once(to, ", ")?;
+    Ok((from.trim().to_owned(), [left.to_owned(), right.to_owned()]))
+}
+
+pub fn parse(input: &str) -> Result<Network, ParseError> {
+    let mut commands: Vec<usize> = Vec::new();
+    let mut map: HashMap<String, [String; 2]> = HashMap::new();
+    input::parse_lines(Day8::DAY, input, |line| {
+        match line.number() {
+            1 => commands = parse_commands(line)?,
+            2 => {}
+            _ => {
+                let (from, to) = parse_node(line)?;
+                map.insert(from, to);
+            }
+        }
+        Ok(())
+    })?;
+    Ok((commands, map))
 }
 
-pub fn solver() {
-    let input = get_input("./src/day8/input.txt");
-    let sum_part_one = solve_part_one(&input);
-    println!("Part 1: {sum_part_one}");
-    let sum_part_two = solve_part_two(&input);
-    println!("Part 2: {sum_part_two}");
+pub fn get_input(file: &str) -> Result<Network, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+    type Input = Network;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve_part_one(input).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        solve_part_two(input).map(|moves| moves.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day8_example_input_part_one() {
-        let input = get_input("./src/day8/example_input.txt");
+        let input = get_input("./src/day8/example_input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(2, sum_part_one);
     }
 
     #[test]
     fn day8_example_input_two_part_one() {
-        let input = get_input("./src/day8/example_input_two.txt");
+        let input = get_input("./src/day8/example_input_two.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(6, sum_part_one);
     }
 
     #[test]
     fn day8_input_part_one() {
-        let input = get_input("./src/day8/input.txt");
+        let input = get_input("./src/day8/input.txt").unwrap();
         let sum_part_one = solve_part_one(&input);
         assert_eq!(11567, sum_part_one);
     }
 
     #[test]
     fn day8_example_input_part_two() {
-        let input = get_input("./src/day8/example_input_part_two.txt");
+        let input = get_input("./src/day8/example_input_part_two.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
-        assert_eq!(6, sum_part_two);
+        assert_eq!(Some(6), sum_part_two);
     }
 
     #[test]
     fn day8_input_part_two() {
-        let input = get_input("./src/day8/input.txt");
+        let input = get_input("./src/day8/input.txt").unwrap();
         let sum_part_two = solve_part_two(&input);
-        assert_eq!(9858474970153, sum_part_two);
-    }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day8/input.txt");
-        b.iter(|| solve_part_one(&input))
+        assert_eq!(Some(9858474970153), sum_part_two);
     }
 
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day8/input.txt");
-        b.iter(|| solve_part_two(&input))
+    #[test]
+    fn day8_ghosts_that_never_meet() {
+        let input = "L\n\n11A = (11Z, 11Z)\n11Z = (11A, 11A)\n22A = (22B, 22B)\n\
+                     22B = (22Z, 22Z)\n22Z = (22B, 22B)";
+        assert_eq!(None, solve_part_two(&parse(input).unwrap()));
     }
 }
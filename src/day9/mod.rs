@@ -1,4 +1,14 @@
-use std::{collections::HashSet, fs};
+//! Day 9: Mirage Maintenance.
+//!
+//! [`solve`] extrapolates every history with any `next_value` function after transforming it with
+//! `sequence_func`, part two reverses the histories to extrapolate backwards.
+
+use std::collections::HashSet;
+
+use crate::{
+    input::{self, Line, ParseError},
+    solution::Solution,
+};
 
 fn get_differences(sequence: &Vec<i128>) -> Vec<i128> {
     sequence
@@ -7,7 +17,8 @@ fn get_differences(sequence: &Vec<i128>) -> Vec<i128> {
         .collect()
 }
 
-fn find_next_value(sequence: &Vec<i128>) -> i128 {
+/// The value after the end of `sequence`, from the differences of the differences.
+pub fn find_next_value(sequence: &Vec<i128>) -> i128 {
     let sequence_set: HashSet<i128> = HashSet::from_iter(sequence.iter().cloned());
     if sequence_set.len() == 1 {
         return *sequence.first().unwrap();
@@ -16,7 +27,8 @@ fn find_next_value(sequence: &Vec<i128>) -> i128 {
     return next_value;
 }
 
-fn find_next_value_iterative(sequence: &Vec<i128>) -> i128 {
+/// Same as [`find_next_value`] without recursion, sums up the last value of every difference.
+pub fn find_next_value_iterative(sequence: &Vec<i128>) -> i128 {
     let mut current_sequence = sequence.clone();
     let mut next_values: Vec<i128> = vec![];
     loop {
@@ -30,24 +42,12 @@ fn find_next_value_iterative(sequence: &Vec<i128>) -> i128 {
     return next_values.iter().sum();
 }
 
-fn parse_sequences(input: &Vec<String>) -> Vec<Vec<i128>> {
-    input
-        .iter()
-        .map(|line| {
-            line.split_whitespace()
-                .into_iter()
-                .map(|string| string.parse::<i128>().unwrap())
-                .collect::<Vec<i128>>()
-        })
-        .collect()
-}
-
-fn solve(
-    input: &Vec<String>,
+/// Sum of `next_value` over all sequences, each one transformed by `sequence_func` first.
+pub fn solve(
+    sequences: &Vec<Vec<i128>>,
     next_value: fn(&Vec<i128>) -> i128,
     sequence_func: fn(&mut Vec<i128>),
 ) -> i128 {
-    let sequences = parse_sequences(input);
     sequences
         .iter()
         .map(|sequence| {
@@ -58,97 +58,83 @@ fn solve(
         .sum()
 }
 
-fn get_input(file: &str) -> Vec<String> {
-    let input = fs::read_to_string(file).expect("Should have been able to read the file");
-    let lines: Vec<String> = input
-        .split("\r\n")
-        .into_iter()
-        .map(|s| s.to_owned())
-        .collect();
-    return lines;
+fn parse_sequence(line: Line) -> Result<Vec<i128>, ParseError> {
+    line.text()
+        .split_whitespace()
+        .map(|string| line.parse::<i128>(string, "a number"))
+        .collect()
+}
+
+pub fn parse(input: &str) -> Result<Vec<Vec<i128>>, ParseError> {
+    input::parse_lines(Day9::DAY, input, parse_sequence)
 }
 
-pub fn solver() {
-    let input = get_input("./src/day9/input.txt");
-    let part_one = solve(&input, find_next_value, |_| {});
-    let part_two = solve(&input, find_next_value, |s| s.reverse());
-    println!("Part 1: {part_one}");
-    println!("Part 2: {part_two}");
-
-    let part_one_iterative = solve(&input, find_next_value_iterative, |_| {});
-    let part_two_iterative = solve(&input, find_next_value_iterative, |s| s.reverse());
-    println!("Part 1 iterative: {part_one_iterative}");
-    println!("Part 2 iterative: {part_two_iterative}");
+pub fn get_input(file: &str) -> Result<Vec<Vec<i128>>, ParseError> {
+    parse(&input::read_file(file))
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+    type Input = Vec<Vec<i128>>;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part_one(input: &Self::Input) -> String {
+        solve(input, find_next_value, |_| {}).to_string()
+    }
+
+    fn part_two(input: &Self::Input) -> Option<String> {
+        Some(solve(input, find_next_value, |s| s.reverse()).to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
 
     #[test]
     fn day9_example_input_part_one() {
-        let input = get_input("./src/day9/example_input.txt");
+        let input = get_input("./src/day9/example_input.txt").unwrap();
         let sum_part_one = solve(&input, find_next_value, |_| {});
         assert_eq!(114, sum_part_one);
     }
 
     #[test]
     fn day9_input_part_one() {
-        let input = get_input("./src/day9/input.txt");
+        let input = get_input("./src/day9/input.txt").unwrap();
         let sum_part_one = solve(&input, find_next_value, |_| {});
         assert_eq!(1798691765, sum_part_one);
     }
 
     #[test]
     fn day9_input_part_one_iterative() {
-        let input = get_input("./src/day9/input.txt");
+        let input = get_input("./src/day9/input.txt").unwrap();
         let sum_part_one = solve(&input, find_next_value_iterative, |_| {});
         assert_eq!(1798691765, sum_part_one);
     }
 
     #[test]
     fn day9_example_input_part_two() {
-        let input = get_input("./src/day9/example_input.txt");
+        let input = get_input("./src/day9/example_input.txt").unwrap();
         let sum_part_two = solve(&input, find_next_value, |s| s.reverse());
         assert_eq!(2, sum_part_two);
     }
 
     #[test]
     fn day9_input_part_two() {
-        let input = get_input("./src/day9/input.txt");
+        let input = get_input("./src/day9/input.txt").unwrap();
         let sum_part_two = solve(&input, find_next_value, |s| s.reverse());
         assert_eq!(1104, sum_part_two);
     }
 
     #[test]
     fn day9_input_part_two_iterative() {
-        let input = get_input("./src/day9/input.txt");
+        let input = get_input("./src/day9/input.txt").unwrap();
         let sum_part_two = solve(&input, find_next_value_iterative, |s| s.reverse());
         assert_eq!(1104, sum_part_two);
     }
-
-    #[bench]
-    fn bench_part_one(b: &mut Bencher) {
-        let input = get_input("./src/day9/input.txt");
-        b.iter(|| solve(&input, find_next_value, |_| {}))
-    }
-
-    #[bench]
-    fn bench_part_two(b: &mut Bencher) {
-        let input = get_input("./src/day9/input.txt");
-        b.iter(|| solve(&input, find_next_value, |s| s.reverse()))
-    }
-
-    #[bench]
-    fn bench_part_one_iterative(b: &mut Bencher) {
-        let input = get_input("./src/day9/input.txt");
-        b.iter(|| solve(&input, find_next_value_iterative, |s| s.reverse()))
-    }
-
-    #[bench]
-    fn bench_part_two_iterative(b: &mut Bencher) {
-        let input = get_input("./src/day9/input.txt");
-        b.iter(|| solve(&input, find_next_value_iterative, |_| {}))
-    }
 }
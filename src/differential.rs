@@ -0,0 +1,199 @@
+//! Differential tests between the alternative solvers of a day.
+//!
+//! Every [`Pair`] computes one answer with two implementations on generated inputs. Sizes are
+//! tried from small to large, a disagreement is then shrunk further by dropping single lines as
+//! long as the answers still differ.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+};
+
+use crate::{
+    day14, day18, day9,
+    generate::{self, Rng},
+    runner::panic_message,
+};
+
+/// Two implementations of the same answer.
+pub struct Pair {
+    pub day: u32,
+    pub name: &'static str,
+    generate: fn(&mut Rng, usize) -> String,
+    /// Both answers, `None` for inputs that do not parse.
+    answers: fn(&str) -> Option<(String, String)>,
+    /// Whether an input stays valid when lines are dropped.
+    drop_lines: bool,
+}
+
+fn day9_answers(input: &str) -> Option<(String, String)> {
+    let sequences = day9::parse(input).ok()?;
+    Some((
+        day9::solve(&sequences, day9::find_next_value, |_| {}).to_string(),
+        day9::solve(&sequences, day9::find_next_value_iterative, |_| {}).to_string(),
+    ))
+}
+
+fn day14_answers(input: &str) -> Option<(String, String)> {
+    let platform = day14::parse(input).ok()?;
+    Some((
+        day14::solve_part_two(&platform, 1000, true).to_string(),
+        day14::solve_part_two(&platform, 1000, false).to_string(),
+    ))
+}
+
+fn day18_answers(input: &str) -> Option<(String, String)> {
+    let plan = day18::parse(input).ok()?;
+    Some((
+        day18::solve_part_one(&plan).to_string(),
+        day18::solve_part_one_with_part_two_solver(&plan).to_string(),
+    ))
+}
+
+pub static PAIRS: [Pair; 3] = [
+    Pair {
+        day: 9,
+        name: "find_next_value vs find_next_value_iterative",
+        generate: |rng, size| generate::day9(rng, size, size + 1),
+        answers: day9_answers,
+        drop_lines: true,
+    },
+    Pair {
+        day: 14,
+        name: "solve_part_two cached vs uncached",
+        generate: generate::day14,
+        answers: day14_answers,
+        drop_lines: true,
+    },
+    Pair {
+        // The dig plan has to stay a closed loop, no line can be dropped.
+        day: 18,
+        name: "solve_part_one vs solve_part_one_with_part_two_solver",
+        generate: generate::day18,
+        answers: day18_answers,
+        drop_lines: false,
+    },
+];
+
+/// The smallest input found on which the two solvers of [`Pair`] differ.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub day: u32,
+    pub name: &'static str,
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    pub answers: (String, String),
+}
+
+impl fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} {}: {} != {} (seed {}, size {}) for",
+            self.day, self.name, self.answers.0, self.answers.1, self.seed, self.size
+        )?;
+        write!(f, "{}", self.input)
+    }
+}
+
+// Both answers if they differ, a panic counts as an answer.
+fn disagreement(pair: &Pair, input: &str) -> Option<(String, String)> {
+    let (one, two) = panic::catch_unwind(AssertUnwindSafe(|| (pair.answers)(input)))
+        .unwrap_or_else(|payload| {
+            let message = format!("panic: {}", panic_message(payload.as_ref()));
+            Some((message.to_owned(), message))
+        })?;
+    (one != two).then_some((one, two))
+}
+
+fn shrink(
+    pair: &Pair,
+    mut input: String,
+    mut answers: (String, String),
+) -> (String, (String, String)) {
+    let mut index = 0;
+    loop {
+        let lines: Vec<&str> = input.lines().collect();
+        if index >= lines.len() || lines.len() == 1 {
+            return (input, answers);
+        }
+        let mut smaller = lines.clone();
+        smaller.remove(index);
+        let smaller = smaller.join("\n");
+        match disagreement(pair, &smaller) {
+            Some(smaller_answers) => {
+                input = smaller;
+                answers = smaller_answers;
+            }
+            None => index += 1,
+        }
+    }
+}
+
+/// Compares the solvers of `pair` on `seeds` inputs of every size up to `max_size`.
+///
+/// Returns the number of inputs that agreed, or the first disagreement by size shrunk to the
+/// fewest lines.
+pub fn check(pair: &Pair, seeds: u64, max_size: usize) -> Result<usize, Disagreement> {
+    let mut checked = 0;
+    for size in 1..=max_size {
+        for seed in 0..seeds {
+            let input = (pair.generate)(&mut Rng::new(seed), size);
+            if let Some(answers) = disagreement(pair, &input) {
+                let (input, answers) = if pair.drop_lines {
+                    shrink(pair, input, answers)
+                } else {
+                    (input, answers)
+                };
+                return Err(Disagreement {
+                    day: pair.day,
+                    name: pair.name,
+                    seed,
+                    size,
+                    input,
+                    answers,
+                });
+            }
+            checked += 1;
+        }
+    }
+    Ok(checked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn alternative_solvers_agree() {
+        for pair in &PAIRS {
+            if let Err(disagreement) = check(pair, 10, 12) {
+                panic!("{disagreement}");
+            }
+        }
+    }
+
+    #[test]
+    fn disagreements_are_shrunk() {
+        // Last values that differ only on sequences containing 7.
+        let pair = Pair {
+            day: 0,
+            name: "last value",
+            generate: |rng, size| generate::day9(rng, size, 3),
+            answers: |input| {
+                let last: Vec<&str> = input
+                    .lines()
+                    .filter_map(|line| line.split(' ').next_back())
+                    .collect();
+                let sevens = input.lines().filter(|line| line.contains('7')).count();
+                Some((last.len().to_string(), (last.len() + sevens).to_string()))
+            },
+            drop_lines: true,
+        };
+        let disagreement = check(&pair, 20, 20).unwrap_err();
+        assert_eq!(1, disagreement.input.lines().count());
+        assert!(disagreement.input.contains('7'));
+        assert_eq!(("1".to_owned(), "2".to_owned()), disagreement.answers);
+    }
+}
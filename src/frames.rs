@@ -0,0 +1,275 @@
+//! Numbered frames of the simulations, to assemble animations from.
+//!
+//! The simulations of days 14, 20, 21 and 22 take a hook that sees their state after every step.
+//! [`capture`] turns those states into [`Scene`]s and [`FrameWriter`] writes them as
+//! `frame_00000.txt` and onwards, or as images.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    day14, day20, day21,
+    day22::{self, Block},
+    grid::Grid,
+    image::ImageFormat,
+    input::ParseError,
+    render::{self, Color, Overlay, Scene},
+};
+
+/// The days [`capture`] can record.
+pub const DAYS: [u32; 4] = [14, 20, 21, 22];
+
+/// Runs the simulation of `day` on the puzzle text `input` and calls `on_frame` with the starting
+/// state and the state after every step, `None` for days without a simulation.
+///
+/// `steps` are the spin cycles of day 14 (3 by default), the button presses of day 20 (1) and the
+/// steps of day 21 (64). Day 22 lets every brick fall once.
+pub fn capture(
+    day: u32,
+    input: &str,
+    steps: Option<usize>,
+    on_frame: &mut dyn FnMut(Scene),
+) -> Option<Result<(), ParseError>> {
+    let result = match day {
+        14 => day14::parse(input).map(|platform| {
+            on_frame(render::platform_scene(platform.clone()));
+            day14::spin_cycles(&platform, steps.unwrap_or(3), &mut |tilted| {
+                on_frame(render::platform_scene(tilted.clone()))
+            });
+        }),
+        20 => day20::parse(input).map(|configuration| {
+            day20::press_button(
+                &configuration,
+                steps.unwrap_or(1),
+                &mut |signal, modules| on_frame(pulse_scene(signal, modules)),
+            );
+        }),
+        21 => day21::parse(input).map(|garden| {
+            let start = garden.position(|tile| *tile == 'S');
+            on_frame(render::garden_scene(garden.clone(), start));
+            day21::reachable_plots(&garden, steps.unwrap_or(64), &mut |reachable| {
+                on_frame(render::garden_scene(
+                    garden.clone(),
+                    reachable.iter().copied(),
+                ))
+            });
+        }),
+        22 => day22::parse(input).map(|blocks| {
+            let blocks = day22::sort_blocks(&blocks);
+            let height = blocks.iter().map(|block| block.end[2]).max().unwrap_or(0);
+            let width = blocks
+                .iter()
+                .map(|block| block.end[0] + 1)
+                .max()
+                .unwrap_or(0);
+            on_frame(bricks_scene(&[], &blocks, width, height));
+            day22::settle_blocks(&blocks, &mut |settled| {
+                on_frame(bricks_scene(
+                    settled,
+                    &blocks[settled.len()..],
+                    width,
+                    height,
+                ))
+            });
+        }),
+        _ => return None,
+    };
+    Some(result)
+}
+
+// The modules in the order of their names below the pulse that just arrived, the row of the
+// receiving module is highlighted.
+fn pulse_scene(signal: &day20::Signal, modules: &HashMap<String, day20::Module>) -> Scene {
+    let mut names: Vec<&String> = modules.keys().collect();
+    names.sort();
+    let pulse = if signal.high_pulse { "high" } else { "low" };
+    let mut lines = vec![format!("{} -{pulse}-> {}", signal.from, signal.to)];
+    lines.extend(names.iter().map(|name| modules[*name].state()));
+
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0);
+    let rows: Vec<Vec<char>> = lines
+        .iter()
+        .map(|line| format!("{line:width$}").chars().collect())
+        .collect();
+    let receiver = names.iter().position(|name| **name == signal.to);
+    let (name, color) = if signal.high_pulse {
+        ("high pulse", Color::Red)
+    } else {
+        ("low pulse", Color::Blue)
+    };
+    let highlighted = receiver
+        .into_iter()
+        .flat_map(|row| (0..width).map(move |col| (row + 1, col)));
+    Scene {
+        overlays: vec![Overlay::new(name, color, '*', highlighted)],
+        grid: Grid::from_rows(rows),
+        outline: None,
+    }
+}
+
+// Side view of the bricks along the y axis as in the puzzle text, `height` is the highest cube of
+// all bricks and the bottom row is the ground. The last settled brick is highlighted.
+fn bricks_scene(settled: &[Block], falling: &[Block], width: usize, height: usize) -> Scene {
+    let mut grid = Grid::new(width, height + 1, '.');
+    for col in 0..width {
+        grid[(height, col)] = '-';
+    }
+    let cubes = |blocks: &[Block]| -> Vec<(usize, usize)> {
+        blocks
+            .iter()
+            .flat_map(|block| {
+                (block.start[2]..=block.end[2]).flat_map(move |z| {
+                    (block.start[0]..=block.end[0]).map(move |x| (height - z, x))
+                })
+            })
+            .collect()
+    };
+    let (last, resting) = match settled.split_last() {
+        Some((last, resting)) => (std::slice::from_ref(last), resting),
+        None => (settled, settled),
+    };
+    Scene {
+        grid,
+        overlays: vec![
+            Overlay::new("falling", Color::Yellow, '+', cubes(falling)),
+            Overlay::new("settled", Color::Blue, '#', cubes(resting)),
+            Overlay::new("just settled", Color::Red, '@', cubes(last)),
+        ],
+        outline: None,
+    }
+}
+
+/// Writes frames into a directory as text, or as images when a format is given.
+pub struct FrameWriter {
+    directory: PathBuf,
+    format: Option<ImageFormat>,
+    scale: usize,
+    count: usize,
+    error: Option<io::Error>,
+}
+
+impl FrameWriter {
+    /// The directory is created with the first frame.
+    pub fn new(directory: &Path, format: Option<ImageFormat>, scale: usize) -> FrameWriter {
+        FrameWriter {
+            directory: directory.to_owned(),
+            format,
+            scale,
+            count: 0,
+            error: None,
+        }
+    }
+
+    pub fn path(&self, index: usize) -> PathBuf {
+        let extension = self.format.map_or("txt", |format| format.extension());
+        self.directory.join(format!("frame_{index:05}.{extension}"))
+    }
+
+    /// Frames after a failed write are dropped, [`FrameWriter::finish`] reports the error.
+    pub fn write(&mut self, scene: &Scene) {
+        if self.error.is_some() {
+            return;
+        }
+        let bytes = match self.format {
+            None => scene.to_plain().into_bytes(),
+            Some(ImageFormat::Ppm) => scene.to_image(self.scale).to_ppm(),
+            Some(ImageFormat::Png) => scene.to_image(self.scale).to_png(),
+            Some(ImageFormat::Svg) => scene.to_svg(self.scale).into_bytes(),
+        };
+        let written = if self.count == 0 {
+            fs::create_dir_all(&self.directory)
+        } else {
+            Ok(())
+        }
+        .and_then(|_| fs::write(self.path(self.count), bytes));
+        match written {
+            Ok(()) => self.count += 1,
+            Err(error) => self.error = Some(error),
+        }
+    }
+
+    /// The number of frames written.
+    pub fn finish(self) -> io::Result<usize> {
+        match self.error {
+            Some(error) => Err(error),
+            None => Ok(self.count),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input;
+
+    fn frames(day: u32, steps: Option<usize>) -> Vec<Scene> {
+        let example = input::read_file(&format!("./src/day{day}/example_input.txt"));
+        let mut frames: Vec<Scene> = Vec::new();
+        capture(day, &example, steps, &mut |scene| frames.push(scene))
+            .unwrap()
+            .unwrap();
+        frames
+    }
+
+    #[test]
+    fn frames_day14_tilts() {
+        let frames = frames(14, Some(1));
+        assert_eq!(5, frames.len());
+        let example = input::read_file("./src/day14/example_input.txt");
+        let platform = day14::parse(&example).unwrap();
+        let cycled = day14::spin_cycles(&platform, 1, &mut |_| {});
+        assert_eq!(cycled, frames[4].grid);
+    }
+
+    #[test]
+    fn frames_day21_frontier() {
+        let frames = frames(21, Some(6));
+        let reachable: Vec<usize> = frames
+            .iter()
+            .map(|frame| frame.overlays[0].positions.len())
+            .collect();
+        assert_eq!(vec![1, 2, 4, 6, 9, 13, 16], reachable);
+    }
+
+    #[test]
+    fn frames_day22_bricks() {
+        let frames = frames(22, None);
+        assert_eq!(8, frames.len());
+        let first_settled = frames[1].to_plain();
+        assert!(first_settled.starts_with(".+.\n.+.\n...\n+++\n..+\n+..\n+++\n+++\n.@.\n---\n"));
+        // The x view of the settled bricks in the puzzle text.
+        let last = frames.last().unwrap().to_plain();
+        assert!(last.starts_with("...\n...\n...\n.@.\n.@.\n###\n#.#\n###\n.#.\n---\n"));
+    }
+
+    #[test]
+    fn frames_day20_pulses() {
+        let frames = frames(20, None);
+        // One press of the example sends 7 low and 4 high pulses after the button's.
+        assert_eq!(11, frames.len());
+        let first = frames[0].to_plain();
+        assert!(first.starts_with("broadcaster -low-> a\n*****"));
+    }
+
+    #[test]
+    fn frames_written_numbered() {
+        let directory =
+            std::env::temp_dir().join(format!("aoc_2023_frames_{}", std::process::id()));
+        let mut writer = FrameWriter::new(&directory, None, 1);
+        for scene in frames(21, Some(2)) {
+            writer.write(&scene);
+        }
+        assert_eq!(3, writer.finish().unwrap());
+        let last = fs::read_to_string(directory.join("frame_00002.txt")).unwrap();
+        assert!(last.contains("O reachable (4 tiles)"));
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(capture(1, "", None, &mut |_| {}).is_none());
+    }
+}
@@ -0,0 +1,409 @@
+//! Seeded random puzzle inputs for stress and scaling tests.
+//!
+//! Every generator returns the puzzle text as it would be read from `input.txt`, the same seed
+//! and sizes always give the same text. [`generate`] picks the generator of a day with the other
+//! sizes derived from one `size` parameter.
+
+use std::ops::Range;
+
+/// SplitMix64, small and good enough to pick puzzle inputs.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Panics on an empty range.
+    pub fn range(&mut self, range: Range<i128>) -> i128 {
+        assert!(range.start < range.end, "Cannot pick from an empty range");
+        let width = (range.end - range.start) as u128;
+        let value = ((self.next_u64() as u128) << 64 | self.next_u64() as u128) % width;
+        range.start + value as i128
+    }
+
+    /// A value in `0..bound`.
+    pub fn below(&mut self, bound: usize) -> usize {
+        self.range(0..bound as i128) as usize
+    }
+
+    /// `true` with a chance of `percent` out of 100.
+    pub fn chance(&mut self, percent: u32) -> bool {
+        self.below(100) < percent as usize
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i + 1));
+        }
+    }
+}
+
+// Lowercase names "aa", "ab", .. skipping the `reserved` ones.
+fn names(count: usize, reserved: &[&str]) -> Vec<String> {
+    (0..)
+        .map(|index: usize| {
+            let letter = |digit: usize| char::from(b'a' + digit as u8);
+            let mut name = String::new();
+            let mut rest = index;
+            loop {
+                name.insert(0, letter(rest % 26));
+                rest /= 26;
+                if rest == 0 {
+                    break;
+                }
+            }
+            if name.len() == 1 {
+                name.insert(0, 'a');
+            }
+            name
+        })
+        .filter(|name| !reserved.contains(&name.as_str()))
+        .take(count)
+        .collect()
+}
+
+/// `sequences` histories of `length` values, each one a polynomial of degree up to five.
+pub fn day9(rng: &mut Rng, sequences: usize, length: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for _ in 0..sequences {
+        let coefficients: Vec<i128> = (0..rng.range(1..7)).map(|_| rng.range(-9..10)).collect();
+        let values: Vec<String> = (0..length as i128)
+            .map(|x| {
+                coefficients
+                    .iter()
+                    .rev()
+                    .fold(0, |value, coefficient| value * x + coefficient)
+                    .to_string()
+            })
+            .collect();
+        lines.push(values.join(" "));
+    }
+    lines.join("\n")
+}
+
+/// A `size` by `size` platform with rounded rocks `O` and cube rocks `#`.
+pub fn day14(rng: &mut Rng, size: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for _ in 0..size {
+        lines.push(
+            (0..size)
+                .map(|_| match rng.below(10) {
+                    0..=2 => 'O',
+                    3 => '#',
+                    _ => '.',
+                })
+                .collect(),
+        );
+    }
+    lines.join("\n")
+}
+
+/// A dig plan around `columns` columns of random height above and below the start.
+///
+/// The trench follows the top of the columns to the right and their bottom back to the left, it
+/// never crosses itself. The colour codes encode the same instructions.
+pub fn day18(rng: &mut Rng, columns: usize) -> String {
+    let columns = columns.max(1);
+    let size = columns as i128;
+    let widths: Vec<i128> = (0..columns).map(|_| rng.range(1..size + 1)).collect();
+    let tops: Vec<i128> = (0..columns).map(|_| -rng.range(1..size + 1)).collect();
+    let bottoms: Vec<i128> = (0..columns).map(|_| rng.range(1..size + 1)).collect();
+
+    let mut moves: Vec<(char, i128)> = Vec::new();
+    let mut row = 0;
+    let mut vertical = |moves: &mut Vec<(char, i128)>, target: i128| {
+        if target < row {
+            moves.push(('U', row - target));
+        } else if target > row {
+            moves.push(('D', target - row));
+        }
+        row = target;
+    };
+    for (top, width) in tops.iter().zip(&widths) {
+        vertical(&mut moves, *top);
+        moves.push(('R', *width));
+    }
+    for (bottom, width) in bottoms.iter().zip(&widths).rev() {
+        vertical(&mut moves, *bottom);
+        moves.push(('L', *width));
+    }
+    vertical(&mut moves, 0);
+
+    moves
+        .into_iter()
+        .map(|(direction, steps)| {
+            let digit = match direction {
+                'R' => 0,
+                'D' => 1,
+                'L' => 2,
+                _ => 3,
+            };
+            format!("{direction} {steps} (#{steps:05x}{digit})")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// `rows` spring records of `length` springs with about half of them unknown.
+pub fn day12(rng: &mut Rng, rows: usize, length: usize) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for _ in 0..rows {
+        let mut springs: Vec<char> = (0..length)
+            .map(|_| if rng.chance(50) { '#' } else { '.' })
+            .collect();
+        if !springs.contains(&'#') {
+            springs[rng.below(length)] = '#';
+        }
+        let groups: Vec<String> = springs
+            .split(|spring| *spring == '.')
+            .filter(|group| !group.is_empty())
+            .map(|group| group.len().to_string())
+            .collect();
+        let springs: String = springs
+            .into_iter()
+            .map(|spring| if rng.chance(50) { '?' } else { spring })
+            .collect();
+        lines.push(format!("{springs} {}", groups.join(",")));
+    }
+    lines.join("\n")
+}
+
+/// `workflows` workflows forming a tree below `in` and `parts` parts.
+pub fn day19(rng: &mut Rng, workflows: usize, parts: usize) -> String {
+    let mut names = names(workflows.saturating_sub(1), &["in"]);
+    names.insert(0, "in".to_owned());
+    let mut targets: Vec<Vec<String>> = vec![Vec::new(); names.len()];
+    for child in 1..names.len() {
+        targets[rng.below(child)].push(names[child].to_owned());
+    }
+
+    let mut lines: Vec<String> = Vec::new();
+    for (name, mut targets) in names.iter().zip(targets) {
+        while targets.len() < 2 || rng.chance(30) {
+            targets.push(if rng.chance(50) { "A" } else { "R" }.to_owned());
+        }
+        rng.shuffle(&mut targets);
+        let fallback = targets.pop().unwrap();
+        let mut rules: Vec<String> = targets
+            .into_iter()
+            .map(|target| {
+                let attribute = ['x', 'm', 'a', 's'][rng.below(4)];
+                let compare = if rng.chance(50) { '<' } else { '>' };
+                format!("{attribute}{compare}{}:{target}", rng.range(1..4001))
+            })
+            .collect();
+        rules.push(fallback);
+        lines.push(format!("{name}{{{}}}", rules.join(",")));
+    }
+    lines.push(String::new());
+    for _ in 0..parts {
+        let mut rating = || rng.range(1..4001);
+        lines.push(format!(
+            "{{x={},m={},a={},s={}}}",
+            rating(),
+            rating(),
+            rating(),
+            rating()
+        ));
+    }
+    lines.join("\n")
+}
+
+/// `counters` binary counters of `bits` flip-flops feeding `rx` through conjunctions.
+///
+/// Like the actual puzzle, every counter resets after a period between `2^(bits - 1)` and
+/// `2^bits - 1` button presses, `bits` has to be at least 2.
+pub fn day20(rng: &mut Rng, counters: usize, bits: usize) -> String {
+    assert!(bits >= 2, "A counter needs at least two flip-flops");
+    let mut names = names(counters * (bits + 2) + 1, &["rx"]).into_iter();
+    let mut next_name = || names.next().unwrap();
+    let output = next_name();
+    let mut lines: Vec<String> = Vec::new();
+    let mut starts: Vec<String> = Vec::new();
+    for _ in 0..counters {
+        let flip_flops: Vec<String> = (0..bits).map(|_| next_name()).collect();
+        let hub = next_name();
+        let inverter = next_name();
+        // The lowest and the highest bit are always set, the period uses all flip-flops.
+        let period_bits: Vec<bool> = (0..bits)
+            .map(|bit| bit == 0 || bit == bits - 1 || rng.chance(50))
+            .collect();
+        let mut hub_outputs: Vec<String> = vec![flip_flops[0].to_owned()];
+        for (bit, flip_flop) in flip_flops.iter().enumerate() {
+            let mut outputs: Vec<String> = Vec::new();
+            if let Some(next) = flip_flops.get(bit + 1) {
+                outputs.push(next.to_owned());
+            }
+            if period_bits[bit] {
+                outputs.push(hub.to_owned());
+            } else {
+                hub_outputs.push(flip_flop.to_owned());
+            }
+            lines.push(format!("%{flip_flop} -> {}", outputs.join(", ")));
+        }
+        hub_outputs.push(inverter.to_owned());
+        lines.push(format!("&{hub} -> {}", hub_outputs.join(", ")));
+        lines.push(format!("&{inverter} -> {output}"));
+        starts.push(flip_flops[0].to_owned());
+    }
+    lines.push(format!("&{output} -> rx"));
+    lines.push(format!("broadcaster -> {}", starts.join(", ")));
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// `bricks` bricks up to four cubes long standing on a `footprint` by `footprint` area.
+pub fn day22(rng: &mut Rng, bricks: usize, footprint: usize) -> String {
+    let footprint = footprint as i128;
+    let mut z = 1;
+    let mut lines: Vec<String> = Vec::new();
+    for _ in 0..bricks {
+        let axis = rng.below(3);
+        let length = rng.range(1..5);
+        let mut start = [rng.range(0..footprint), rng.range(0..footprint), z];
+        let mut end = start;
+        end[axis] += length - 1;
+        if end[axis] >= footprint && axis < 2 {
+            start[axis] = footprint - length.min(footprint);
+            end[axis] = footprint - 1;
+        }
+        // Every brick starts above the previous one, the snapshot never overlaps.
+        z = end[2] + 1 + rng.range(0..3);
+        lines.push(format!(
+            "{},{},{}~{},{},{}",
+            start[0], start[1], start[2], end[0], end[1], end[2]
+        ));
+    }
+    rng.shuffle(&mut lines);
+    lines.join("\n")
+}
+
+/// `hailstones` hailstones that are all hit by one rock thrown from integer coordinates.
+pub fn day24(rng: &mut Rng, hailstones: usize) -> String {
+    let rock: Vec<i128> = (0..3)
+        .map(|_| rng.range(200_000_000_000_000..400_000_000_000_000))
+        .collect();
+    let rock_velocity: Vec<i128> = (0..3).map(|_| rng.range(-300..301)).collect();
+    let mut lines: Vec<String> = Vec::new();
+    for _ in 0..hailstones {
+        let time = rng.range(10_000_000_000..100_000_000_000);
+        let velocity: Vec<i128> = loop {
+            let velocity: Vec<i128> = (0..3).map(|_| rng.range(-300..301)).collect();
+            if velocity != rock_velocity {
+                break velocity;
+            }
+        };
+        let position: Vec<i128> = (0..3)
+            .map(|axis| rock[axis] + (rock_velocity[axis] - velocity[axis]) * time)
+            .collect();
+        lines.push(format!(
+            "{}, {}, {} @ {}, {}, {}",
+            position[0], position[1], position[2], velocity[0], velocity[1], velocity[2]
+        ));
+    }
+    lines.join("\n")
+}
+
+/// An input for `day` scaled by `size`, `None` for days without a generator.
+pub fn generate(day: u32, seed: u64, size: usize) -> Option<String> {
+    let rng = &mut Rng::new(seed);
+    match day {
+        9 => Some(day9(rng, size, 21)),
+        12 => Some(day12(rng, size, 20)),
+        14 => Some(day14(rng, size)),
+        18 => Some(day18(rng, size)),
+        19 => Some(day19(rng, size, size * 2)),
+        20 => Some(day20(rng, size, 12)),
+        22 => Some(day22(rng, size, 10)),
+        24 => Some(day24(rng, size)),
+        _ => None,
+    }
+}
+
+/// The days [`generate`] has a generator for.
+pub const DAYS: [u32; 8] = [9, 12, 14, 18, 19, 20, 22, 24];
+
+/// The `size` of the actual puzzle input, `None` for days without a generator.
+///
+/// Day 20 multiplies the periods of its counters, each below 2^12. Up to five always fit into
+/// 64 bits, with more part two may find no answer and give `None`.
+pub fn default_size(day: u32) -> Option<usize> {
+    match day {
+        9 => Some(200),
+        12 => Some(1000),
+        14 => Some(100),
+        18 => Some(170),
+        19 => Some(576),
+        20 => Some(4),
+        22 => Some(1500),
+        24 => Some(300),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{day12, day14, day18, day19, day20, day22, day24, day9};
+
+    #[test]
+    fn generate_is_deterministic() {
+        for day in DAYS {
+            assert_eq!(generate(day, 7, 10), generate(day, 7, 10));
+            assert_ne!(generate(day, 7, 10), generate(day, 8, 10));
+        }
+        assert_eq!(None, generate(1, 7, 10));
+        assert!(DAYS.iter().all(|day| default_size(*day).is_some()));
+        assert_eq!(vec!["aa", "ab", "ac"], names(3, &[]));
+        assert_eq!("ba", names(27, &["in"])[26]);
+    }
+
+    #[test]
+    fn generated_inputs_parse_and_solve() {
+        let sequences = day9::parse(&generate(9, 1, 20).unwrap()).unwrap();
+        assert!(sequences.iter().all(|sequence| sequence.len() == 21));
+
+        let platform = day14::parse(&generate(14, 1, 20).unwrap()).unwrap();
+        assert_eq!((20, 20), (platform.width(), platform.height()));
+
+        let plan = day18::parse(&generate(18, 1, 20).unwrap()).unwrap();
+        assert_eq!(day18::solve_part_one(&plan), day18::solve_part_two(&plan));
+
+        let records = day12::parse(&generate(12, 1, 20).unwrap()).unwrap();
+        assert_eq!(20, records.len());
+        assert!(day12::solve_part_one(&records) >= 20);
+
+        let system = day19::parse(&generate(19, 1, 30).unwrap()).unwrap();
+        assert_eq!((30, 60), (system.0.len(), system.1.len()));
+        assert!(day19::solve_part_two(&system) <= 4000usize.pow(4));
+
+        let bricks = day22::parse(&generate(22, 1, 40).unwrap()).unwrap();
+        assert!(day22::solve_part_one(&bricks) <= 40);
+
+        let hailstones = day24::parse(&generate(24, 1, 20).unwrap()).unwrap();
+        assert_eq!(20, hailstones.len());
+    }
+
+    #[test]
+    fn generated_counters_set_the_rx_period() {
+        let configuration = day20::parse(&day20(&mut Rng::new(3), 1, 6)).unwrap();
+        let period = day20::solve_part_two(&configuration).unwrap();
+        assert!((32..64).contains(&period), "period {period}");
+        let configuration = day20::parse(&generate(20, 3, 4).unwrap()).unwrap();
+        assert!(day20::solve_part_two(&configuration).unwrap() >= 2048);
+        // The periods of many counters multiply beyond 64 bits.
+        let configuration = day20::parse(&generate(20, 0, 8).unwrap()).unwrap();
+        assert_eq!(None, day20::solve_part_two(&configuration));
+    }
+}
@@ -0,0 +1,196 @@
+use std::ops::{Add, Mul, Sub};
+
+/// A position or offset on a map, rows grow downwards and columns to the right.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub row: i128,
+    pub col: i128,
+}
+
+impl Point {
+    pub const fn new(row: i128, col: i128) -> Point {
+        Point { row, col }
+    }
+
+    pub fn manhattan(&self, other: Point) -> i128 {
+        (self.row - other.row).abs() + (self.col - other.col).abs()
+    }
+
+    /// The grid index of this point, `None` for negative coordinates.
+    pub fn to_index(&self) -> Option<(usize, usize)> {
+        Some((
+            usize::try_from(self.row).ok()?,
+            usize::try_from(self.col).ok()?,
+        ))
+    }
+}
+
+impl From<(usize, usize)> for Point {
+    fn from((row, col): (usize, usize)) -> Point {
+        Point::new(row as i128, col as i128)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.row + other.row, self.col + other.col)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.row - other.row, self.col - other.col)
+    }
+}
+
+impl Mul<i128> for Point {
+    type Output = Point;
+
+    fn mul(self, factor: i128) -> Point {
+        Point::new(self.row * factor, self.col * factor)
+    }
+}
+
+impl Add<Dir> for Point {
+    type Output = Point;
+
+    fn add(self, dir: Dir) -> Point {
+        self + dir.offset()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    /// Clockwise, starting upwards.
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    pub fn offset(&self) -> Point {
+        match self {
+            Dir::Up => Point::new(-1, 0),
+            Dir::Right => Point::new(0, 1),
+            Dir::Down => Point::new(1, 0),
+            Dir::Left => Point::new(0, -1),
+        }
+    }
+
+    /// The direction of a single step, `None` for other offsets.
+    pub fn from_offset(offset: Point) -> Option<Dir> {
+        Dir::ALL.into_iter().find(|dir| dir.offset() == offset)
+    }
+
+    pub fn turn_left(&self) -> Dir {
+        match self {
+            Dir::Up => Dir::Left,
+            Dir::Right => Dir::Up,
+            Dir::Down => Dir::Right,
+            Dir::Left => Dir::Down,
+        }
+    }
+
+    pub fn turn_right(&self) -> Dir {
+        match self {
+            Dir::Up => Dir::Right,
+            Dir::Right => Dir::Down,
+            Dir::Down => Dir::Left,
+            Dir::Left => Dir::Up,
+        }
+    }
+
+    pub fn reverse(&self) -> Dir {
+        match self {
+            Dir::Up => Dir::Down,
+            Dir::Right => Dir::Left,
+            Dir::Down => Dir::Up,
+            Dir::Left => Dir::Right,
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, Dir::Right | Dir::Left)
+    }
+
+    /// `U`, `D`, `L` or `R`.
+    pub fn from_letter(letter: char) -> Option<Dir> {
+        match letter {
+            'U' => Some(Dir::Up),
+            'D' => Some(Dir::Down),
+            'L' => Some(Dir::Left),
+            'R' => Some(Dir::Right),
+            _ => None,
+        }
+    }
+
+    /// The last digit of the day 18 colour codes: `0` right, `1` down, `2` left and `3` up.
+    pub fn from_hex_digit(digit: char) -> Option<Dir> {
+        match digit {
+            '0' => Some(Dir::Right),
+            '1' => Some(Dir::Down),
+            '2' => Some(Dir::Left),
+            '3' => Some(Dir::Up),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir_turns() {
+        for dir in Dir::ALL {
+            assert_eq!(dir, dir.turn_left().turn_right());
+            assert_eq!(dir.reverse(), dir.turn_left().turn_left());
+            assert_eq!(Some(dir), Dir::from_offset(dir.offset()));
+        }
+        assert_eq!(Dir::Right, Dir::Up.turn_right());
+        assert_eq!(Dir::Right, Dir::Down.turn_left());
+        assert_eq!(None, Dir::from_offset(Point::new(1, 1)));
+    }
+
+    #[test]
+    fn dir_parsing() {
+        assert_eq!(
+            vec![
+                Some(Dir::Up),
+                Some(Dir::Down),
+                Some(Dir::Left),
+                Some(Dir::Right),
+                None
+            ],
+            "UDLRX".chars().map(Dir::from_letter).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![
+                Some(Dir::Right),
+                Some(Dir::Down),
+                Some(Dir::Left),
+                Some(Dir::Up),
+                None
+            ],
+            "01234".chars().map(Dir::from_hex_digit).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let point = Point::new(2, 3);
+        assert_eq!(Point::new(1, 3), point + Dir::Up);
+        assert_eq!(Point::new(2, -7), point + Dir::Left.offset() * 10);
+        assert_eq!(7, point.manhattan(Point::new(-1, -1)));
+        assert_eq!(Some((2, 3)), point.to_index());
+        assert_eq!(None, Point::new(-1, 0).to_index());
+        assert_eq!(point, Point::from((2, 3)));
+    }
+}
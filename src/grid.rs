@@ -0,0 +1,336 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use crate::geometry::{Dir, Point};
+
+/// Offsets of all eight surrounding tiles in reading order.
+pub const SURROUNDING: [Point; 8] = [
+    Point::new(-1, -1),
+    Point::new(-1, 0),
+    Point::new(-1, 1),
+    Point::new(0, -1),
+    Point::new(0, 1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+];
+
+/// A rectangular map stored row by row in one buffer, positions are `(row, col)`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        mut cell: impl FnMut((usize, usize)) -> T,
+    ) -> Grid<T> {
+        let cells = (0..height)
+            .flat_map(|row| (0..width).map(move |col| (row, col)))
+            .map(&mut cell)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Panics if the rows differ in length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == width),
+            "All rows of a grid need the same length"
+        );
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, (row, col): (usize, usize)) -> Option<&T> {
+        if row < self.height && col < self.width {
+            Some(&self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, (row, col): (usize, usize)) -> Option<&mut T> {
+        if row < self.height && col < self.width {
+            Some(&mut self.cells[row * self.width + col])
+        } else {
+            None
+        }
+    }
+
+    /// The position of `point`, `None` if it is outside the grid.
+    pub fn checked_position(&self, point: Point) -> Option<(usize, usize)> {
+        point
+            .to_index()
+            .filter(|(row, col)| *row < self.height && *col < self.width)
+    }
+
+    pub fn in_bounds(&self, point: Point) -> bool {
+        self.checked_position(point).is_some()
+    }
+
+    /// The value at `point`, `None` if it is outside the grid.
+    pub fn try_get(&self, point: Point) -> Option<&T> {
+        self.checked_position(point).map(|position| &self[position])
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, row: usize) -> &mut [T] {
+        &mut self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        (0..self.height).map(|row| self.row(row))
+    }
+
+    pub fn column(&self, col: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(col < self.width, "Column {col} is outside of the grid");
+        (0..self.height).map(move |row| &self.cells[row * self.width + col])
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// All positions in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    /// All positions in reading order with their values.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    /// The first position in reading order whose value matches `predicate`.
+    pub fn position(&self, mut predicate: impl FnMut(&T) -> bool) -> Option<(usize, usize)> {
+        self.iter()
+            .find(|(_, value)| predicate(value))
+            .map(|(position, _)| position)
+    }
+
+    /// The up to four orthogonal neighbours of `position` inside the grid.
+    pub fn neighbours(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let point = Point::from(position);
+        Dir::ALL
+            .into_iter()
+            .filter_map(move |dir| self.checked_position(point + dir))
+    }
+
+    /// The up to eight neighbours of `position` inside the grid, diagonals included.
+    pub fn surrounding(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let point = Point::from(position);
+        SURROUNDING
+            .into_iter()
+            .filter_map(move |offset| self.checked_position(point + offset))
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut f).collect(),
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Rows become columns, the result is mirrored along the main diagonal.
+    pub fn transpose(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, row)].clone()
+        })
+    }
+
+    pub fn rotate_clockwise(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(self.height - 1 - col, row)].clone()
+        })
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Grid<T> {
+        Grid::from_fn(self.height, self.width, |(row, col)| {
+            self[(col, self.width - 1 - row)].clone()
+        })
+    }
+
+    /// Reverses every row.
+    pub fn mirror_left_right(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(row, col)| {
+            self[(row, self.width - 1 - col)].clone()
+        })
+    }
+
+    /// Reverses the order of the rows.
+    pub fn mirror_top_bottom(&self) -> Grid<T> {
+        Grid::from_fn(self.width, self.height, |(row, col)| {
+            self[(self.height - 1 - row, col)].clone()
+        })
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (row, col): (usize, usize)) -> &T {
+        self.get((row, col)).unwrap_or_else(|| {
+            panic!(
+                "Position ({row}, {col}) is outside of the {}x{} grid",
+                self.height, self.width
+            )
+        })
+    }
+}
+
+/// Panics for points outside of the grid, like indexing with a position.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        self.try_get(point).unwrap_or_else(|| {
+            panic!(
+                "Point ({}, {}) is outside of the {}x{} grid",
+                point.row, point.col, self.height, self.width
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (row, col): (usize, usize)) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        self.get_mut((row, col)).unwrap_or_else(|| {
+            panic!("Position ({row}, {col}) is outside of the {height}x{width} grid")
+        })
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        match self.checked_position(point) {
+            Some(position) => &mut self[position],
+            None => panic!(
+                "Point ({}, {}) is outside of the {height}x{width} grid",
+                point.row, point.col
+            ),
+        }
+    }
+}
+
+/// One line per row without separators between the cells, like the puzzle inputs.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, row) in self.rows().enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(text: &str) -> Grid<char> {
+        Grid::from_rows(text.lines().map(|line| line.chars().collect()).collect())
+    }
+
+    #[test]
+    fn grid_transforms() {
+        let abc = grid("abc\ndef");
+        assert_eq!(grid("ad\nbe\ncf"), abc.transpose());
+        assert_eq!(grid("da\neb\nfc"), abc.rotate_clockwise());
+        assert_eq!(grid("cf\nbe\nad"), abc.rotate_counter_clockwise());
+        assert_eq!(grid("cba\nfed"), abc.mirror_left_right());
+        assert_eq!(grid("def\nabc"), abc.mirror_top_bottom());
+        assert_eq!(abc, abc.rotate_clockwise().rotate_counter_clockwise());
+        assert_eq!("abc\ndef", abc.to_string());
+    }
+
+    #[test]
+    fn grid_checked_indexing() {
+        let mut abc = grid("abc\ndef");
+        assert_eq!((3, 2), (abc.width(), abc.height()));
+        assert_eq!(Some(&'f'), abc.get((1, 2)));
+        assert_eq!(None, abc.get((2, 0)));
+        assert_eq!(None, abc.get((0, 3)));
+        assert_eq!(Some(&'d'), abc.try_get(Point::new(1, 0)));
+        assert_eq!(None, abc.try_get(Point::new(-1, 0)));
+        assert_eq!('b', abc[Point::new(0, 1)]);
+        abc[(0, 1)] = 'x';
+        assert_eq!(Some((0, 1)), abc.position(|value| *value == 'x'));
+    }
+
+    #[test]
+    fn grid_neighbours_stay_inside() {
+        let abc = grid("abc\ndef");
+        assert_eq!(
+            vec![(0, 1), (1, 0)],
+            abc.neighbours((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)],
+            abc.surrounding((1, 1)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn grid_rows_and_columns() {
+        let abc = grid("abc\ndef");
+        assert_eq!(&['d', 'e', 'f'], abc.row(1));
+        assert_eq!(vec![&'b', &'e'], abc.column(1).collect::<Vec<_>>());
+        assert_eq!(
+            vec!["ad", "be", "cf"],
+            abc.columns()
+                .map(|column| column.collect::<String>())
+                .collect::<Vec<_>>()
+        );
+    }
+}
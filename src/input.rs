@@ -0,0 +1,380 @@
+use std::{
+    error, fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read},
+    path::Path,
+    str::FromStr,
+};
+
+use crate::grid::Grid;
+
+/// Reads a puzzle input from `path`, `-` reads stdin instead.
+pub fn read(path: &Path) -> io::Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    fs::read_to_string(path)
+}
+
+/// Opens a puzzle input at `path` to be read as it is decoded, `-` reads stdin instead.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(BufReader::new(File::open(path)?)))
+}
+
+/// Reads a puzzle input that is known to exist, as used by the day tests.
+pub fn read_file(file: &str) -> String {
+    fs::read_to_string(file).expect("Should have been able to read the file")
+}
+
+/// Converts CRLF and CR line endings to LF and drops trailing newlines.
+pub fn normalize(input: &str) -> String {
+    input
+        .replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .trim_end_matches('\n')
+        .to_owned()
+}
+
+/// Lines of the input, independent of the line endings and without a trailing empty line.
+pub fn lines(input: &str) -> Vec<String> {
+    let input = normalize(input);
+    if input.is_empty() {
+        return vec![];
+    }
+    input.split('\n').map(|s| s.to_owned()).collect()
+}
+
+/// Where an input could not be parsed, lines and columns count from one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: u32,
+    pub line: usize,
+    pub column: usize,
+    pub token: String,
+    pub expected: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}, found ",
+            self.day, self.line, self.column, self.expected
+        )?;
+        if self.token.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "'{}'", self.token)
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// A line of the input that knows its position, so parsers can point at the offending token.
+#[derive(Clone, Copy)]
+pub struct Line<'a> {
+    day: u32,
+    number: usize,
+    text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    pub fn new(day: u32, number: usize, text: &'a str) -> Line<'a> {
+        Line { day, number, text }
+    }
+
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// The empty token after the last character, used when a line ends too early.
+    pub fn end(&self) -> &'a str {
+        &self.text[self.text.len()..]
+    }
+
+    /// `token` has to be a slice of this line, other tokens are reported at the first column.
+    pub fn error(&self, token: &str, expected: &str) -> ParseError {
+        let offset = (token.as_ptr() as usize).wrapping_sub(self.text.as_ptr() as usize);
+        let offset = if offset + token.len() <= self.text.len() {
+            offset
+        } else {
+            0
+        };
+        ParseError {
+            day: self.day,
+            line: self.number,
+            column: self.text[..offset].chars().count() + 1,
+            token: token.to_owned(),
+            expected: expected.to_owned(),
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self, token: &'a str, expected: &str) -> Result<T, ParseError> {
+        token.parse::<T>().map_err(|_| self.error(token, expected))
+    }
+
+    pub fn split_once(
+        &self,
+        text: &'a str,
+        delimiter: &str,
+    ) -> Result<(&'a str, &'a str), ParseError> {
+        text.split_once(delimiter)
+            .ok_or_else(|| self.error(&text[text.len()..], &format!("'{}'", delimiter.trim())))
+    }
+
+    /// The next item of `tokens`, a missing one is reported at the end of the line.
+    pub fn next(
+        &self,
+        tokens: &mut impl Iterator<Item = &'a str>,
+        expected: &str,
+    ) -> Result<&'a str, ParseError> {
+        tokens
+            .next()
+            .ok_or_else(|| self.error(self.end(), expected))
+    }
+}
+
+/// Parses every line of the input with `parse_line`, stopping at the first error.
+pub fn parse_lines<T>(
+    day: u32,
+    input: &str,
+    mut parse_line: impl FnMut(Line) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    lines(input)
+        .iter()
+        .enumerate()
+        .map(|(index, text)| parse_line(Line::new(day, index + 1, text)))
+        .collect()
+}
+
+/// Parses blocks of lines separated by one or more blank lines with `parse_group`.
+pub fn parse_groups<T>(
+    day: u32,
+    input: &str,
+    mut parse_group: impl FnMut(&[Line]) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let lines = lines(input);
+    let mut groups: Vec<T> = Vec::new();
+    let mut group: Vec<Line> = Vec::new();
+    for (index, text) in lines.iter().enumerate() {
+        if !text.is_empty() {
+            group.push(Line::new(day, index + 1, text));
+            continue;
+        }
+        if !group.is_empty() {
+            groups.push(parse_group(&group)?);
+            group.clear();
+        }
+    }
+    if !group.is_empty() {
+        groups.push(parse_group(&group)?);
+    }
+    Ok(groups)
+}
+
+// One row of a grid, `width` is taken from the first row.
+fn parse_grid_row<'a, T>(
+    line: Line<'a>,
+    width: &mut Option<usize>,
+    parse_cell: &mut impl FnMut(Line<'a>, &'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    let row = parse_row(line.text(), |token, _| parse_cell(line, token))?;
+    let expected_width = *width.get_or_insert(row.len());
+    if row.len() < expected_width {
+        return Err(line.error(line.end(), &format!("{expected_width} tiles")));
+    }
+    if row.len() > expected_width {
+        let (offset, _) = line.text().char_indices().nth(expected_width).unwrap();
+        return Err(line.error(&line.text()[offset..], "end of line"));
+    }
+    Ok(row)
+}
+
+// A tile of a character grid, has to be one of `symbols`.
+fn parse_symbol(line: Line, token: &str, symbols: &str) -> Result<char, ParseError> {
+    let symbol = token.chars().next().unwrap();
+    if !symbols.contains(symbol) {
+        return Err(line.error(token, &format!("one of '{symbols}'")));
+    }
+    Ok(symbol)
+}
+
+/// Parses a rectangular grid, `parse_cell` gets the line and the character of every tile.
+pub fn parse_grid_with<T>(
+    day: u32,
+    input: &str,
+    mut parse_cell: impl for<'a> FnMut(Line<'a>, &'a str) -> Result<T, ParseError>,
+) -> Result<Grid<T>, ParseError> {
+    let mut width: Option<usize> = None;
+    let rows = parse_lines(day, input, |line| {
+        parse_grid_row(line, &mut width, &mut parse_cell)
+    })?;
+    Ok(Grid::from_rows(rows))
+}
+
+/// Parses a rectangular grid of characters, each one of `symbols`.
+pub fn parse_grid(day: u32, input: &str, symbols: &str) -> Result<Grid<char>, ParseError> {
+    parse_grid_with(day, input, |line, token| parse_symbol(line, token, symbols))
+}
+
+/// Checks that `symbol` appears exactly once in the input, a second one is reported where it is
+/// and a missing one at the end of the input. `expected` names the symbol, as in `start 'S'`.
+pub fn expect_single(
+    day: u32,
+    input: &str,
+    symbol: char,
+    expected: &str,
+) -> Result<(), ParseError> {
+    let lines = lines(input);
+    let mut seen = false;
+    for (index, text) in lines.iter().enumerate() {
+        let line = Line::new(day, index + 1, text);
+        for (_, token) in text.match_indices(symbol) {
+            if seen {
+                return Err(line.error(token, &format!("a single {expected}")));
+            }
+            seen = true;
+        }
+    }
+    if !seen {
+        let last = lines.last().map_or("", |text| text.as_str());
+        let line = Line::new(day, lines.len().max(1), last);
+        return Err(line.error(line.end(), &format!("a {expected}")));
+    }
+    Ok(())
+}
+
+/// Same as [`parse_grid`] for a group of lines.
+pub fn parse_grid_lines(lines: &[Line], symbols: &str) -> Result<Grid<char>, ParseError> {
+    let mut width: Option<usize> = None;
+    let mut parse_cell = |line, token| parse_symbol(line, token, symbols);
+    let rows = lines
+        .iter()
+        .map(|line| parse_grid_row(*line, &mut width, &mut parse_cell))
+        .collect::<Result<Vec<Vec<char>>, ParseError>>()?;
+    Ok(Grid::from_rows(rows))
+}
+
+/// Parses every character of `text`, `parse_symbol` gets the character as token and its index.
+pub fn parse_row<'a, T>(
+    text: &'a str,
+    mut parse_symbol: impl FnMut(&'a str, usize) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    text.char_indices()
+        .enumerate()
+        .map(|(index, (offset, symbol))| {
+            parse_symbol(&text[offset..offset + symbol.len_utf8()], index)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_ignore_line_endings() {
+        let expected = vec!["467..114..".to_owned(), "...*......".to_owned()];
+        assert_eq!(expected, lines("467..114..\n...*......"));
+        assert_eq!(expected, lines("467..114..\r\n...*......\r\n"));
+        assert_eq!(expected, lines("467..114..\n...*......\n\n"));
+        assert_eq!(Vec::<String>::new(), lines("\r\n"));
+    }
+
+    #[test]
+    fn lines_keep_inner_blank_lines() {
+        assert_eq!(vec!["LLR", "", "AAA"], lines("LLR\r\n\r\nAAA\r\n"));
+    }
+
+    fn groups(input: &str) -> Vec<Vec<String>> {
+        parse_groups(0, input, |group| {
+            Ok(group.iter().map(|line| line.text().to_owned()).collect())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_split_on_blank_lines() {
+        let expected = vec![
+            vec!["seeds: 79 14".to_owned()],
+            vec!["a".to_owned(), "b".to_owned()],
+        ];
+        assert_eq!(expected, groups("seeds: 79 14\n\na\nb\n"));
+        assert_eq!(expected, groups("seeds: 79 14\r\n\r\n\r\na\r\nb\r\n"));
+    }
+
+    #[test]
+    fn example_inputs_parse_the_same_with_crlf() {
+        let input = read_file("./src/day5/example_input.txt");
+        let crlf_input = normalize(&input).replace('\n', "\r\n");
+        assert_eq!(groups(&input), groups(&crlf_input));
+        assert_eq!(8, groups(&crlf_input).len());
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_token() {
+        let line = Line::new(18, 3, "X 6 (#70c710)");
+        let error = line
+            .parse::<u32>(&line.text()[..1], "a number")
+            .unwrap_err();
+        assert_eq!(
+            (3, 1, "X".to_owned()),
+            (error.line, error.column, error.token)
+        );
+        assert_eq!(Ok(6), line.parse::<u32>(&line.text()[2..3], "a number"));
+        let error = line.error(&line.text()[4..], "a colour");
+        assert_eq!(
+            "day 18, line 3, column 5: expected a colour, found '(#70c710)'",
+            error.to_string()
+        );
+        assert_eq!(
+            "day 18, line 3, column 14: expected ')', found end of line",
+            line.split_once(line.end(), ")").unwrap_err().to_string()
+        );
+    }
+
+    #[test]
+    fn grids_have_to_be_rectangular() {
+        assert_eq!(
+            Ok(Grid::from_rows(vec![vec!['.', '#'], vec!['#', '.']])),
+            parse_grid(13, ".#\n#.", ".#")
+        );
+        let error = parse_grid(13, ".#\n#.x", ".#").unwrap_err();
+        assert_eq!(
+            (2, 3, "x".to_owned()),
+            (error.line, error.column, error.token)
+        );
+        let error = parse_grid(13, ".#.\n#.", ".#").unwrap_err();
+        assert_eq!(
+            (2, 3, "3 tiles".to_owned()),
+            (error.line, error.column, error.expected)
+        );
+    }
+
+    #[test]
+    fn single_symbols_are_checked() {
+        assert_eq!(Ok(()), expect_single(10, "..\n.S", 'S', "start 'S'"));
+        assert_eq!(
+            "day 10, line 2, column 4: expected a start 'S', found end of line",
+            expect_single(10, "...\n...", 'S', "start 'S'")
+                .unwrap_err()
+                .to_string()
+        );
+        assert_eq!(
+            "day 10, line 2, column 2: expected a single start 'S', found 'S'",
+            expect_single(10, "S..\n.S.", 'S', "start 'S'")
+                .unwrap_err()
+                .to_string()
+        );
+    }
+}
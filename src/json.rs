@@ -0,0 +1,279 @@
+use std::{fmt, iter::Peekable, str::Chars};
+
+/// Minimal JSON value, enough for the files this crate writes and reads back.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Json::Number(number) => Some(*number),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<Json>> {
+        match self {
+            Json::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter, string: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for symbol in string.chars() {
+        match symbol {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            symbol if (symbol as u32) < 0x20 => write!(f, "\\u{:04x}", symbol as u32)?,
+            symbol => write!(f, "{symbol}")?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{value}"),
+            Json::Number(number) if number.is_finite() => write!(f, "{number}"),
+            Json::Number(_) => write!(f, "null"),
+            Json::String(string) => write_string(f, string),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{value}")?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    offset: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<char> {
+        let symbol = self.chars.next()?;
+        self.offset += 1;
+        Some(symbol)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self
+            .chars
+            .peek()
+            .is_some_and(|symbol| symbol.is_whitespace())
+        {
+            self.next();
+        }
+    }
+
+    fn error(&self, expected: &str) -> String {
+        format!("expected {expected} at character {}", self.offset + 1)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.next() {
+            Some(symbol) if symbol == expected => Ok(()),
+            _ => Err(format!(
+                "expected '{expected}' at character {}",
+                self.offset
+            )),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Json) -> Result<Json, String> {
+        for expected in keyword.chars() {
+            if self.next() != Some(expected) {
+                return Err(self.error(keyword));
+            }
+        }
+        Ok(value)
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next().ok_or_else(|| self.error("'\"'"))? {
+                '"' => return Ok(string),
+                '\\' => match self.next().ok_or_else(|| self.error("an escape"))? {
+                    'n' => string.push('\n'),
+                    'r' => string.push('\r'),
+                    't' => string.push('\t'),
+                    'u' => {
+                        let code: String = (0..4).filter_map(|_| self.next()).collect();
+                        let symbol = u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .ok_or_else(|| self.error("four hexadecimal digits"))?;
+                        string.push(symbol);
+                    }
+                    symbol => string.push(symbol),
+                },
+                symbol => string.push(symbol),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Json, String> {
+        let mut number = String::new();
+        while let Some(symbol) = self.chars.peek() {
+            if !symbol.is_ascii_digit() && !"+-.eE".contains(*symbol) {
+                break;
+            }
+            number.push(*symbol);
+            self.next();
+        }
+        number
+            .parse::<f64>()
+            .map(Json::Number)
+            .map_err(|_| self.error("a number"))
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.chars.peek() {
+            Some('n') => self.keyword("null", Json::Null),
+            Some('t') => self.keyword("true", Json::Bool(true)),
+            Some('f') => self.keyword("false", Json::Bool(false)),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some('[') => {
+                self.next();
+                let mut values: Vec<Json> = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&']') {
+                    self.next();
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Json::Array(values)),
+                        _ => return Err(self.error("',' or ']'")),
+                    }
+                }
+            }
+            Some('{') => {
+                self.next();
+                let mut fields: Vec<(String, Json)> = Vec::new();
+                self.skip_whitespace();
+                if self.chars.peek() == Some(&'}') {
+                    self.next();
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    let name = self.string()?;
+                    self.expect(':')?;
+                    fields.push((name, self.value()?));
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(Json::Object(fields)),
+                        _ => return Err(self.error("',' or '}'")),
+                    }
+                }
+            }
+            _ => self.number(),
+        }
+    }
+}
+
+pub fn parse(input: &str) -> Result<Json, String> {
+    let mut parser = Parser {
+        chars: input.chars().peekable(),
+        offset: 0,
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    if parser.chars.peek().is_some() {
+        return Err(parser.error("end of input"));
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_round_trip() {
+        let value = Json::Object(vec![
+            ("day".to_owned(), Json::Number(17.0)),
+            (
+                "answer".to_owned(),
+                Json::String("a \"quoted\"\n\\".to_owned()),
+            ),
+            (
+                "samples".to_owned(),
+                Json::Array(vec![Json::Number(1.5), Json::Null, Json::Bool(true)]),
+            ),
+            ("empty".to_owned(), Json::Object(vec![])),
+        ]);
+        assert_eq!(Ok(value.clone()), parse(&value.to_string()));
+        assert_eq!(Some(17.0), value.get("day").and_then(Json::as_f64));
+    }
+
+    #[test]
+    fn json_parse_whitespace_and_errors() {
+        let value = parse(" { \"a\" : [ 1 , -2e3 ] , \"b\" : \"\\u0041\" } ").unwrap();
+        assert_eq!(
+            Some(-2000.0),
+            value.get("a").unwrap().as_array().unwrap()[1].as_f64()
+        );
+        assert_eq!(Some("A"), value.get("b").and_then(Json::as_str));
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("{\"a\" 1}").is_err());
+        assert!(parse("1 2").is_err());
+    }
+}
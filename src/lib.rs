@@ -0,0 +1,77 @@
+//! Advent of Code 2023 solutions.
+//!
+//! Every day lives in its own module with the same surface: `parse` turns the puzzle text into
+//! the typed input of that day, `solve_part_one` and `solve_part_two` (or a parameterised `solve`)
+//! compute the answers from it. Parameters that differ between the examples and the actual
+//! puzzle, like the expansion factor of [`day11::solve`] or the step count of
+//! [`day21::solve_part_one`], are arguments of the solve functions.
+//!
+//! ```
+//! use aoc_2023::day6;
+//!
+//! let races = day6::parse("Time:      7  15   30\nDistance:  9  40  200").unwrap();
+//! assert_eq!(288, day6::solve_part_one(&races));
+//! assert_eq!(71503, day6::solve_part_two(&races));
+//! ```
+//!
+//! [`solution::SOLUTIONS`] lists all days behind one object safe interface, [`runner`],
+//! [`bench`](mod@bench) and [`verify`] build the commands of the binary on top of it.
+
+// The puzzle modules favour explicit returns, late initialisation, index loops and `&Vec`
+// parameters.
+#![allow(
+    clippy::needless_return,
+    clippy::needless_late_init,
+    clippy::needless_range_loop,
+    clippy::ptr_arg,
+    clippy::type_complexity
+)]
+
+pub mod bench;
+pub mod cycle;
+pub mod differential;
+pub mod frames;
+pub mod generate;
+pub mod geometry;
+pub mod grid;
+pub mod image;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod math;
+pub mod memory;
+pub mod render;
+pub mod runner;
+pub mod solution;
+pub mod verify;
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+
+// The unit tests count allocations like a binary built with the alloc-count feature, the memory
+// and bench tests depend on it.
+#[cfg(test)]
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
@@ -0,0 +1,123 @@
+//! Leveled diagnostics on stderr, answers on stdout stay clean.
+//!
+//! The level is global and defaults to [`Level::Warn`]. The binary lowers it with `--quiet` and
+//! raises it with `--verbose`. The solvers log through [`debug!`](crate::debug) and
+//! [`trace!`](crate::trace), which skip formatting entirely below the level, and tell with
+//! [`warn!`](crate::warn) when they find no answer.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    /// Intermediate results of a solver.
+    Debug,
+    /// Everything, like every step of a simulation.
+    Trace,
+}
+
+impl Level {
+    const ALL: [Level; 5] = [
+        Level::Error,
+        Level::Warn,
+        Level::Info,
+        Level::Debug,
+        Level::Trace,
+    ];
+
+    /// `Warn` raised by `verbose` steps, `Error` when `quiet`.
+    pub fn from_verbosity(verbose: u32, quiet: bool) -> Level {
+        if quiet {
+            return Level::Error;
+        }
+        Level::ALL[(Level::Warn as usize + verbose as usize).min(Level::ALL.len() - 1)]
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Level::Error => write!(f, "error"),
+            Level::Warn => write!(f, "warning"),
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace"),
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    Level::ALL[LEVEL.load(Ordering::Relaxed) as usize]
+}
+
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Use the macros, they check the level before formatting.
+pub fn write(level: Level, message: fmt::Arguments) {
+    eprintln!("{level}: {message}");
+}
+
+/// Logs a message at the given [`Level`](crate::log::Level).
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($arg:tt)+) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)+));
+        }
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Warn, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Info, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Debug, $($arg)+)
+    };
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::log!($crate::log::Level::Trace, $($arg)+)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_levels_from_verbosity() {
+        assert_eq!(Level::Warn, Level::from_verbosity(0, false));
+        assert_eq!(Level::Debug, Level::from_verbosity(2, false));
+        assert_eq!(Level::Trace, Level::from_verbosity(7, false));
+        assert_eq!(Level::Error, Level::from_verbosity(3, true));
+        assert!(Level::Error < Level::Trace);
+    }
+}
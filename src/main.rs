@@ -1,32 +1,456 @@
-#![feature(test)]
-
-extern crate test;
-
-// pub mod day1;
-// pub mod day2;
-// pub mod day3;
-// pub mod day4;
-// pub mod day5;
-// pub mod day6;
-// pub mod day7;
-// pub mod day8;
-// pub mod day9;
-// pub mod day10;
-// pub mod day11;
-// pub mod day12;
-// pub mod day13;
-// pub mod day14;
-// pub mod day15;
-// pub mod day16;
-// pub mod day17;
-// pub mod day18;
-// pub mod day19;
-// pub mod day20;
-// pub mod day21;
-// pub mod day22;
-// pub mod day23;
-pub mod day24;
-
-fn main() {
-    day24::solver();
+use std::{
+    env, fs, panic,
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
+
+use aoc_2023::{
+    bench::{self, BenchError},
+    day1::{Vocabulary, VocabularyError},
+    day2,
+    differential,
+    frames::{self, FrameWriter},
+    generate,
+    image::ImageFormat,
+    input,
+    log::{self, Level},
+    render,
+    runner::{self, Format, RunError},
+    solution::{Part, SOLUTIONS},
+    verify,
+};
+use cli::{BenchArgs, Command, DifferentialArgs, FramesArgs, GenerateArgs, RenderArgs, RunArgs, VerifyArgs};
+
+mod cli;
+
+#[cfg(feature = "alloc-count")]
+#[global_allocator]
+static ALLOCATOR: aoc_2023::memory::Counting = aoc_2023::memory::Counting;
+
+const EXIT_INPUT: u8 = 1;
+const EXIT_USAGE: u8 = 2;
+const EXIT_UNAVAILABLE: u8 = 3;
+const EXIT_INVALID_INPUT: u8 = 4;
+const EXIT_REGRESSION: u8 = 5;
+const EXIT_MISMATCH: u8 = 6;
+const EXIT_PANIC: u8 = 7;
+
+fn exit_code(error: &RunError) -> ExitCode {
+    match error {
+        RunError::MissingInput(_) => ExitCode::from(EXIT_INPUT),
+        RunError::InvalidInput(_) => ExitCode::from(EXIT_INVALID_INPUT),
+        RunError::UnknownDay(_) | RunError::UnknownPart(_) | RunError::Unsolved(_, _) => {
+            ExitCode::from(EXIT_UNAVAILABLE)
+        }
+        RunError::Panicked(_, _, _) => ExitCode::from(EXIT_PANIC),
+    }
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let parts = match args.part {
+        Some(number) => match Part::from_number(number) {
+            Some(part) => vec![part],
+            None => {
+                let error = RunError::UnknownPart(number);
+                eprintln!("error: {error}");
+                return exit_code(&error);
+            }
+        },
+        None => vec![Part::One, Part::Two],
+    };
+    let Some(day) = args.day else {
+        return run_all(&parts, args.format);
+    };
+    let input = args.input.unwrap_or_else(|| runner::default_input(day));
+
+    let bag = match args.bag.as_deref().map(read_bag).transpose() {
+        Ok(bag) => bag,
+        Err(code) => return code,
+    };
+    let vocabulary = match read_vocabulary(args.vocabulary.as_deref()) {
+        Ok(vocabulary) => vocabulary,
+        Err(code) => return code,
+    };
+    // A panic is reported as an error, the default hook would print it a second time.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = match bag {
+        Some(bag) => runner::run_puzzle(&day2::WithBag(bag), &parts, &input),
+        None if day == 1 => runner::run_day1(&parts, &input, &vocabulary),
+        None => runner::run(day, &parts, &input),
+    };
+    panic::set_hook(hook);
+    let results = match result {
+        Ok(results) => results,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return exit_code(&error);
+        }
+    };
+    // A failed part is reported after the answers of the others.
+    let mut reports: Vec<runner::Report> = Vec::new();
+    let mut errors: Vec<RunError> = Vec::new();
+    for result in results {
+        match result {
+            Ok(report) => reports.push(report),
+            Err(error) => errors.push(error),
+        }
+    }
+    // Only an explicitly requested part is an error when it is not solved yet.
+    if args.part.is_some() && reports.iter().any(|report| report.answer.is_none()) {
+        let error = RunError::Unsolved(day, reports[0].part);
+        eprintln!("error: {error}");
+        return exit_code(&error);
+    }
+    match args.format {
+        Format::Text => {
+            for report in reports {
+                match report.answer {
+                    Some(answer) => println!("Part {}: {answer}", report.part),
+                    None if log::enabled(Level::Warn) => {
+                        eprintln!("Part {}: not solved yet", report.part)
+                    }
+                    None => {}
+                }
+            }
+        }
+        Format::Json => println!("{}", runner::to_json(&reports)),
+        Format::Csv => print!("{}", runner::format_csv(&reports)),
+    }
+    for error in &errors {
+        eprintln!("error: {error}");
+    }
+    errors.first().map_or(ExitCode::SUCCESS, exit_code)
+}
+
+// The bag of day 2 from a file, or written out on the command line.
+fn read_bag(bag: &str) -> Result<day2::Draw, ExitCode> {
+    let path = Path::new(bag);
+    let text = if path.is_file() {
+        fs::read_to_string(path).map_err(|_| {
+            eprintln!("error: cannot read bag {}", path.display());
+            ExitCode::from(EXIT_INPUT)
+        })?
+    } else {
+        bag.to_owned()
+    };
+    day2::parse_bag(&text).map_err(|error| {
+        eprintln!("error: invalid bag, {error}");
+        ExitCode::from(EXIT_INVALID_INPUT)
+    })
+}
+
+// The spelled digits of day 1, a preset or a file, English without `--vocabulary`.
+fn read_vocabulary(name: Option<&str>) -> Result<Vocabulary, ExitCode> {
+    let Some(name) = name else {
+        return Ok(Vocabulary::english());
+    };
+    Vocabulary::load(name).map_err(|error| match error {
+        VocabularyError::Unreadable(_) => {
+            eprintln!("error: {error}");
+            ExitCode::from(EXIT_INPUT)
+        }
+        _ => {
+            eprintln!("error: invalid vocabulary, {error}");
+            ExitCode::from(EXIT_INVALID_INPUT)
+        }
+    })
+}
+
+fn run_all(parts: &[Part], format: Format) -> ExitCode {
+    let days: Vec<u32> = SOLUTIONS.iter().map(|solution| solution.day()).collect();
+    // Panics are reported in the table, the default hook would interleave them with it.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let tasks = runner::run_all(&days, parts);
+    panic::set_hook(hook);
+
+    match format {
+        Format::Text => print!("{}", runner::format_tasks(&tasks)),
+        Format::Json | Format::Csv => {
+            let reports: Vec<runner::Report> = tasks
+                .iter()
+                .filter_map(|task| task.result.as_ref().ok().cloned())
+                .collect();
+            for error in tasks.iter().filter_map(|task| task.result.as_ref().err()) {
+                eprintln!("error: {error}");
+            }
+            match format {
+                Format::Json => println!("{}", runner::to_json(&reports)),
+                _ => print!("{}", runner::format_csv(&reports)),
+            }
+        }
+    }
+    // Days are ordered by runtime, report the earliest day that failed.
+    match tasks
+        .iter()
+        .filter_map(|task| Some((task.day, task.result.as_ref().err()?)))
+        .min_by_key(|(day, _)| *day)
+    {
+        Some((_, error)) => exit_code(error),
+        None => ExitCode::SUCCESS,
+    }
+}
+
+fn bench_exit_code(error: &BenchError) -> ExitCode {
+    match error {
+        BenchError::Run(error) => exit_code(error),
+        BenchError::UnstableAnswer(_, _) => ExitCode::from(EXIT_REGRESSION),
+        BenchError::Baseline(_, _) => ExitCode::from(EXIT_INPUT),
+        BenchError::NotCounting => ExitCode::from(EXIT_USAGE),
+    }
+}
+
+fn bench(args: BenchArgs) -> ExitCode {
+    if args.alloc {
+        return bench_allocations(args.day);
+    }
+    let options = bench::Options {
+        warmup: args.warmup,
+        iterations: args.iterations,
+    };
+    let days: Vec<u32> = match args.day {
+        Some(day) => vec![day],
+        None => SOLUTIONS.iter().map(|solution| solution.day()).collect(),
+    };
+    // Read the baseline first, a broken file should not cost a full benchmark run.
+    let baseline = match args
+        .baseline
+        .as_deref()
+        .map(bench::load_baseline)
+        .transpose()
+    {
+        Ok(baseline) => baseline,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return bench_exit_code(&error);
+        }
+    };
+
+    let mut measurements: Vec<bench::Measurement> = Vec::new();
+    for day in days {
+        match bench::measure(day, &runner::default_input(day), &options) {
+            Ok(day_measurements) => measurements.extend(day_measurements),
+            Err(error) => {
+                eprintln!("error: {error}");
+                return bench_exit_code(&error);
+            }
+        }
+    }
+    print!("{}", bench::format_measurements(&measurements));
+
+    if let Some(path) = &args.save {
+        if let Err(error) = bench::save_baseline(path, &options, &measurements) {
+            eprintln!("error: {error}");
+            return bench_exit_code(&error);
+        }
+    }
+    if let Some(baseline) = baseline {
+        let comparisons = bench::compare(&baseline, &measurements, args.threshold as f64);
+        print!("\n{}", bench::format_comparisons(&comparisons));
+        if comparisons
+            .iter()
+            .any(|comparison| comparison.verdict.is_regression())
+        {
+            return ExitCode::from(EXIT_REGRESSION);
+        }
+    }
+    ExitCode::SUCCESS
+}
+
+fn bench_allocations(day: Option<u32>) -> ExitCode {
+    let days: Vec<u32> = match day {
+        Some(day) => vec![day],
+        None => SOLUTIONS.iter().map(|solution| solution.day()).collect(),
+    };
+    let mut allocations: Vec<bench::Allocation> = Vec::new();
+    for day in days {
+        match bench::measure_allocations(day, &runner::default_input(day)) {
+            Ok(day_allocations) => allocations.extend(day_allocations),
+            Err(error) => {
+                eprintln!("error: {error}");
+                return bench_exit_code(&error);
+            }
+        }
+    }
+    print!("{}", bench::format_allocations(&allocations));
+    ExitCode::SUCCESS
+}
+
+fn verify(args: VerifyArgs) -> ExitCode {
+    let manifest = args.manifest.unwrap_or_else(verify::default_manifest);
+    let entries = match verify::load_manifest(&manifest) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("error: {error}");
+            return ExitCode::from(EXIT_INPUT);
+        }
+    };
+    let entries: Vec<verify::Entry> = entries
+        .into_iter()
+        .filter(|entry| args.day.is_none_or(|day| day == entry.day))
+        .collect();
+
+    let outcomes = verify::verify(&entries);
+    print!("{}", verify::format_outcomes(&outcomes));
+    if outcomes.iter().all(|outcome| outcome.status.is_pass()) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_MISMATCH)
+    }
+}
+
+fn differential(args: DifferentialArgs) -> ExitCode {
+    // Panics count as answers and are part of the report.
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let mut agreed = true;
+    for pair in differential::PAIRS
+        .iter()
+        .filter(|pair| args.day.is_none_or(|day| day == pair.day))
+    {
+        match differential::check(pair, args.seeds as u64, args.size as usize) {
+            Ok(checked) => println!("day {} {}: {checked} inputs agree", pair.day, pair.name),
+            Err(disagreement) => {
+                println!("{disagreement}");
+                agreed = false;
+            }
+        }
+    }
+    panic::set_hook(hook);
+    if agreed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::from(EXIT_MISMATCH)
+    }
+}
+
+fn generate(args: GenerateArgs) -> ExitCode {
+    let size = args
+        .size
+        .map(|size| size as usize)
+        .or_else(|| generate::default_size(args.day));
+    match size.and_then(|size| generate::generate(args.day, args.seed, size)) {
+        Some(input) => {
+            println!("{input}");
+            ExitCode::SUCCESS
+        }
+        None => {
+            eprintln!(
+                "error: there is no generator for day {}, expected one of {:?}",
+                args.day,
+                generate::DAYS
+            );
+            ExitCode::from(EXIT_UNAVAILABLE)
+        }
+    }
+}
+
+// The puzzle text at `path`, the default input of `day` if there is none.
+fn read_puzzle(day: u32, path: Option<PathBuf>) -> Result<String, ExitCode> {
+    let path = path.unwrap_or_else(|| runner::default_input(day));
+    input::read(&path).map_err(|_| {
+        eprintln!("error: {}", RunError::MissingInput(path));
+        ExitCode::from(EXIT_INPUT)
+    })
+}
+
+fn render(args: RenderArgs) -> ExitCode {
+    let text = match read_puzzle(args.day, args.input) {
+        Ok(text) => text,
+        Err(exit_code) => return exit_code,
+    };
+    match render::scene(args.day, &text, args.steps) {
+        Some(Ok(scene)) => {
+            if let Some((path, format)) = args.output {
+                let bytes = match format {
+                    ImageFormat::Ppm => scene.to_image(args.scale).to_ppm(),
+                    ImageFormat::Png => scene.to_image(args.scale).to_png(),
+                    ImageFormat::Svg => scene.to_svg(args.scale).into_bytes(),
+                };
+                if let Err(error) = fs::write(&path, bytes) {
+                    eprintln!("error: cannot write image {}, {error}", path.display());
+                    return ExitCode::from(EXIT_INPUT);
+                }
+                return ExitCode::SUCCESS;
+            }
+            // https://no-color.org: any non-empty value disables colours.
+            let no_color = env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty());
+            if args.plain || no_color {
+                print!("{}", scene.to_plain());
+            } else {
+                print!("{}", scene.to_ansi());
+            }
+            ExitCode::SUCCESS
+        }
+        Some(Err(error)) => {
+            eprintln!("error: {}", RunError::InvalidInput(error));
+            ExitCode::from(EXIT_INVALID_INPUT)
+        }
+        None => {
+            eprintln!(
+                "error: there is no renderer for day {}, expected one of {:?}",
+                args.day,
+                render::DAYS
+            );
+            ExitCode::from(EXIT_UNAVAILABLE)
+        }
+    }
+}
+
+fn frames(args: FramesArgs) -> ExitCode {
+    let text = match read_puzzle(args.day, args.input) {
+        Ok(text) => text,
+        Err(exit_code) => return exit_code,
+    };
+    let directory = args
+        .output
+        .unwrap_or_else(|| PathBuf::from(format!("./frames/day{}", args.day)));
+    let mut writer = FrameWriter::new(&directory, args.format, args.scale);
+    match frames::capture(args.day, &text, args.steps, &mut |scene| writer.write(&scene)) {
+        Some(Ok(())) => match writer.finish() {
+            Ok(count) => {
+                println!("wrote {count} frames to {}", directory.display());
+                ExitCode::SUCCESS
+            }
+            Err(error) => {
+                eprintln!("error: cannot write frames to {}, {error}", directory.display());
+                ExitCode::from(EXIT_INPUT)
+            }
+        },
+        Some(Err(error)) => {
+            eprintln!("error: {}", RunError::InvalidInput(error));
+            ExitCode::from(EXIT_INVALID_INPUT)
+        }
+        None => {
+            eprintln!(
+                "error: there is no simulation to record for day {}, expected one of {:?}",
+                args.day,
+                frames::DAYS
+            );
+            ExitCode::from(EXIT_UNAVAILABLE)
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let (level, args) = cli::split_verbosity(env::args().skip(1));
+    log::set_level(level);
+    match cli::parse_args(args) {
+        Ok(Command::Run(args)) => run(args),
+        Ok(Command::Bench(args)) => bench(args),
+        Ok(Command::Verify(args)) => verify(args),
+        Ok(Command::Differential(args)) => differential(args),
+        Ok(Command::Generate(args)) => generate(args),
+        Ok(Command::Render(args)) => render(args),
+        Ok(Command::Frames(args)) => frames(args),
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Err(error) => {
+            eprintln!("error: {error}\n\n{}", cli::USAGE);
+            ExitCode::from(EXIT_USAGE)
+        }
+    }
 }
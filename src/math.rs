@@ -0,0 +1,149 @@
+//! Exact number theory for the solvers.
+//!
+//! [`crt`] combines congruences whose moduli share factors, [`isqrt`] and [`isqrt_big`] take
+//! integer square roots without going through `f64`, and [`Rational`] with [`solve_linear_2x2`]
+//! keeps fractions of large coordinates exact.
+
+use num::{rational::Ratio, BigUint, Integer, Zero};
+
+/// A fraction of two `i128`, always reduced.
+pub type Rational = Ratio<i128>;
+
+/// The numbers that have every `(remainder, modulus)` of `congruences`, as their smallest one and
+/// the modulus they repeat with. Moduli need not be coprime and remainders may exceed them, `None`
+/// if the congruences contradict each other or their combined modulus does not fit into `u64`.
+pub fn crt(congruences: &[(u64, u64)]) -> Option<(u64, u64)> {
+    congruences.iter().try_fold((0, 1), |combined, congruence| {
+        crt_pair(combined, *congruence)
+    })
+}
+
+fn crt_pair(
+    (remainder, modulus): (u64, u64),
+    (other_remainder, other_modulus): (u64, u64),
+) -> Option<(u64, u64)> {
+    let remainder = remainder % modulus;
+    let other_remainder = other_remainder % other_modulus;
+    let gcd = modulus.gcd(&other_modulus);
+    let difference = other_remainder as i128 - remainder as i128;
+    if difference % gcd as i128 != 0 {
+        return None;
+    }
+    // remainder + modulus * k has the other remainder for k = difference / gcd * inverse.
+    let reduced_modulus = other_modulus / gcd;
+    let inverse = mod_inverse(modulus / gcd % reduced_modulus, reduced_modulus)?;
+    let step = (difference / gcd as i128).rem_euclid(reduced_modulus as i128) as u128;
+    let k = step * inverse as u128 % reduced_modulus as u128;
+    let lcm = modulus.checked_mul(reduced_modulus)?;
+    Some(((remainder as u128 + modulus as u128 * k) as u64, lcm))
+}
+
+/// The `x` with `a * x` equal to 1 modulo `modulus`, `None` if `a` and `modulus` share a factor.
+pub fn mod_inverse(a: u64, modulus: u64) -> Option<u64> {
+    if modulus == 1 {
+        return Some(0);
+    }
+    let (gcd, x, _) = extended_gcd(a as i128, modulus as i128);
+    (gcd == 1).then(|| x.rem_euclid(modulus as i128) as u64)
+}
+
+// gcd(a, b) with x and y such that a * x + b * y = gcd(a, b).
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - a / b * y)
+    }
+}
+
+/// The first number from `from` onwards with the `remainder` modulo `modulus`.
+pub fn first_from((remainder, modulus): (u64, u64), from: u64) -> u64 {
+    let remainder = remainder % modulus;
+    if remainder >= from {
+        remainder
+    } else {
+        remainder + (from - remainder).div_ceil(modulus) * modulus
+    }
+}
+
+/// The largest `x` with `x * x <= n`.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from above stops once it no longer decreases.
+    let mut x = 1u128 << (128 - n.leading_zeros()).div_ceil(2);
+    loop {
+        let next = (x + n / x) / 2;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// [`isqrt`] for numbers beyond `u128`.
+pub fn isqrt_big(n: &BigUint) -> BigUint {
+    if n.bits() < 2 {
+        return n.clone();
+    }
+    let mut x = BigUint::from(1u8) << n.bits().div_ceil(2);
+    loop {
+        let next = (&x + n / &x) >> 1;
+        if next >= x {
+            return x;
+        }
+        x = next;
+    }
+}
+
+/// The `x` and `y` with `a[0][0] * x + a[0][1] * y = b[0]` and `a[1][0] * x + a[1][1] * y = b[1]`
+/// by Cramer's rule, `None` if the rows are parallel.
+pub fn solve_linear_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<(Rational, Rational)> {
+    let determinant = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    if determinant.is_zero() {
+        return None;
+    }
+    let x = b[0] * a[1][1] - a[0][1] * b[1];
+    let y = a[0][0] * b[1] - b[0] * a[1][0];
+    Some((Rational::new(x, determinant), Rational::new(y, determinant)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn math_crt() {
+        assert_eq!(Some((23, 105)), crt(&[(2, 3), (3, 5), (2, 7)]));
+        // Shared factors, remainders beyond their moduli.
+        assert_eq!(Some((10, 12)), crt(&[(10, 4), (4, 6)]));
+        assert_eq!(None, crt(&[(1, 4), (2, 6)]));
+        assert_eq!(Some((0, 1)), crt(&[]));
+        assert_eq!(None, crt(&[(1, u64::MAX), (2, u64::MAX - 1)]));
+        assert_eq!(Some(22), crt(&[(2, 4), (1, 3)]).map(|c| first_from(c, 15)));
+        assert_eq!(Some(5), mod_inverse(3, 7));
+        assert_eq!(None, mod_inverse(4, 6));
+    }
+
+    #[test]
+    fn math_isqrt() {
+        for n in 0..1000u128 {
+            let root = isqrt(n);
+            assert!(root * root <= n && (root + 1) * (root + 1) > n);
+        }
+        assert_eq!(u64::MAX as u128, isqrt(u128::MAX));
+        assert_eq!(1 << 63, isqrt(1 << 126));
+        let big = BigUint::from(u128::MAX) * BigUint::from(u128::MAX);
+        assert_eq!(BigUint::from(u128::MAX), isqrt_big(&big));
+        assert_eq!(BigUint::from(u128::MAX - 1), isqrt_big(&(big - 1u8)));
+    }
+
+    #[test]
+    fn math_solve_linear_2x2() {
+        let (x, y) = solve_linear_2x2([[2, 1], [1, 3]], [3, 5]).unwrap();
+        assert_eq!((Rational::new(4, 5), Rational::new(7, 5)), (x, y));
+        assert_eq!(None, solve_linear_2x2([[2, 4], [1, 2]], [1, 1]));
+    }
+}
@@ -0,0 +1,140 @@
+//! Counting heap allocations, to find the solvers that copy the most.
+//!
+//! [`Counting`] wraps the system allocator and only adds to a few atomic counters, the binary
+//! installs it as its global allocator when built with the `alloc-count` feature, so timings are
+//! not skewed otherwise. [`measure`] reports what a closure allocated, it reads zeros when
+//! [`Counting`] is not installed, which [`is_counting`] tells.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static BYTES: AtomicUsize = AtomicUsize::new(0);
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator counting allocations, allocated bytes and the most bytes in use at once.
+/// A reallocation counts as an allocation of its new size.
+pub struct Counting;
+
+fn allocated(size: usize) {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(size, Ordering::Relaxed);
+    let current = CURRENT.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(current, Ordering::Relaxed);
+}
+
+fn freed(size: usize) {
+    CURRENT.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            allocated(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc_zeroed(layout);
+        if !pointer.is_null() {
+            allocated(layout.size());
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        freed(layout.size());
+    }
+
+    unsafe fn realloc(&self, pointer: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_pointer = System.realloc(pointer, layout, new_size);
+        if !new_pointer.is_null() {
+            freed(layout.size());
+            allocated(new_size);
+        }
+        new_pointer
+    }
+}
+
+/// What a closure allocated, `peak` are the most bytes it had in use at once on top of what was
+/// allocated before.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    pub allocations: usize,
+    pub bytes: usize,
+    pub peak: usize,
+}
+
+/// Runs `f` and counts its allocations. Allocations of other threads in the meantime are counted
+/// as well, and measurements must not be nested.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (Usage, T) {
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let bytes = BYTES.load(Ordering::Relaxed);
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    let result = f();
+    let usage = Usage {
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+        bytes: BYTES.load(Ordering::Relaxed) - bytes,
+        peak: PEAK.load(Ordering::Relaxed).saturating_sub(current),
+    };
+    (usage, result)
+}
+
+/// Whether [`Counting`] is the global allocator.
+pub fn is_counting() -> bool {
+    let (usage, _) = measure(|| hint::black_box(Box::new(0u64)));
+    usage.allocations > 0
+}
+
+/// `1536` as `1.5 KiB`.
+pub fn format_bytes(bytes: usize) -> String {
+    let units = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", units[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn memory_measure_counts_allocations() {
+        // The test binary counts with the allocator installed in lib.rs.
+        assert!(is_counting());
+        let (usage, sum) = measure(|| {
+            let numbers: Vec<u64> = (0..1000).collect();
+            let doubled: Vec<u64> = numbers.iter().map(|n| n * 2).collect();
+            drop(numbers);
+            doubled.iter().sum::<u64>()
+        });
+        assert_eq!(999000, sum);
+        // Other tests run alongside, the counts can only be larger.
+        assert!(usage.allocations >= 2);
+        assert!(usage.bytes >= 16000);
+        assert!(usage.peak > 0);
+    }
+
+    #[test]
+    fn memory_format_bytes() {
+        assert_eq!("512 B", format_bytes(512));
+        assert_eq!("1.5 KiB", format_bytes(1536));
+        assert_eq!("3.0 MiB", format_bytes(3 << 20));
+    }
+}
@@ -0,0 +1,436 @@
+//! Draws the maps of the grid puzzles with coloured overlays of what the solvers found.
+//!
+//! A [`Scene`] is a map with overlays on top, later overlays cover earlier ones. [`scene`] builds
+//! the scene of a day from its puzzle text with the parameters of the actual puzzle. Scenes are
+//! drawn as text with ANSI colours or as images, see [`Scene::to_image`] and [`Scene::to_svg`].
+
+use std::collections::HashSet;
+
+use crate::{
+    day10, day11, day14, day16, day17, day18, day21, day23,
+    geometry::{Dir, Point},
+    grid::Grid,
+    image::{Image, Rgb},
+    input::ParseError,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi_background(&self) -> u8 {
+        match self {
+            Color::Red => 41,
+            Color::Green => 42,
+            Color::Yellow => 43,
+            Color::Blue => 44,
+            Color::Magenta => 45,
+            Color::Cyan => 46,
+        }
+    }
+
+    fn rgb(&self) -> Rgb {
+        match self {
+            Color::Red => [220, 50, 47],
+            Color::Green => [80, 170, 60],
+            Color::Yellow => [240, 190, 40],
+            Color::Blue => [40, 110, 210],
+            Color::Magenta => [200, 60, 170],
+            Color::Cyan => [40, 180, 190],
+        }
+    }
+}
+
+const BACKGROUND: Rgb = [250, 250, 250];
+
+// Open tiles and padding are left blank, walls are dark and digits get darker the higher they are.
+fn tile_rgb(tile: char) -> Rgb {
+    match tile {
+        '.' | ' ' => BACKGROUND,
+        '#' => [60, 60, 60],
+        '0'..='9' => {
+            let shade = 240 - 20 * tile.to_digit(10).unwrap() as u8;
+            [shade, shade, shade]
+        }
+        _ => [170, 170, 170],
+    }
+}
+
+fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Highlighted tiles, `symbol` replaces them when drawing without colours.
+pub struct Overlay {
+    pub name: &'static str,
+    pub color: Color,
+    pub symbol: char,
+    pub positions: HashSet<(usize, usize)>,
+}
+
+impl Overlay {
+    pub fn new(
+        name: &'static str,
+        color: Color,
+        symbol: char,
+        positions: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Overlay {
+        Overlay {
+            name,
+            color,
+            symbol,
+            positions: positions.into_iter().collect(),
+        }
+    }
+
+    /// Points outside of the grid are left out.
+    pub fn from_points(
+        name: &'static str,
+        color: Color,
+        symbol: char,
+        points: impl IntoIterator<Item = Point>,
+    ) -> Overlay {
+        Overlay::new(
+            name,
+            color,
+            symbol,
+            points.into_iter().filter_map(|point| point.to_index()),
+        )
+    }
+}
+
+pub struct Scene {
+    pub grid: Grid<char>,
+    pub overlays: Vec<Overlay>,
+    /// Corners of a shape through the tile centres, drawn as a polygon by [`Scene::to_svg`].
+    pub outline: Option<Vec<Point>>,
+}
+
+impl Scene {
+    /// The topmost overlay covering `position`.
+    pub fn overlay_at(&self, position: (usize, usize)) -> Option<&Overlay> {
+        self.overlays
+            .iter()
+            .rev()
+            .find(|overlay| overlay.positions.contains(&position))
+    }
+
+    /// The map with overlay tiles on coloured backgrounds, followed by a legend.
+    pub fn to_ansi(&self) -> String {
+        let mut text = String::new();
+        for row in 0..self.grid.height() {
+            for col in 0..self.grid.width() {
+                let tile = self.grid[(row, col)];
+                match self.overlay_at((row, col)) {
+                    Some(overlay) => {
+                        text +=
+                            &format!("\x1b[30;{}m{tile}\x1b[0m", overlay.color.ansi_background())
+                    }
+                    None => text.push(tile),
+                }
+            }
+            text.push('\n');
+        }
+        for overlay in &self.overlays {
+            text += &format!(
+                "\n\x1b[30;{}m \x1b[0m {} ({} tiles)",
+                overlay.color.ansi_background(),
+                overlay.name,
+                overlay.positions.len()
+            );
+        }
+        text.push('\n');
+        text
+    }
+
+    /// The map with overlay tiles replaced by their symbols, followed by a legend.
+    pub fn to_plain(&self) -> String {
+        let mut text = String::new();
+        for row in 0..self.grid.height() {
+            for col in 0..self.grid.width() {
+                match self.overlay_at((row, col)) {
+                    Some(overlay) => text.push(overlay.symbol),
+                    None => text.push(self.grid[(row, col)]),
+                }
+            }
+            text.push('\n');
+        }
+        for overlay in &self.overlays {
+            text += &format!(
+                "\n{} {} ({} tiles)",
+                overlay.symbol,
+                overlay.name,
+                overlay.positions.len()
+            );
+        }
+        text.push('\n');
+        text
+    }
+
+    fn rgb_at(&self, position: (usize, usize)) -> Rgb {
+        match self.overlay_at(position) {
+            Some(overlay) => overlay.color.rgb(),
+            None => tile_rgb(self.grid[position]),
+        }
+    }
+
+    /// Every tile as a `scale` by `scale` square.
+    pub fn to_image(&self, scale: usize) -> Image {
+        let mut image = Image::new(
+            self.grid.width() * scale,
+            self.grid.height() * scale,
+            BACKGROUND,
+        );
+        for (row, col) in self.grid.positions() {
+            image.fill_square(col * scale, row * scale, scale, self.rgb_at((row, col)));
+        }
+        image
+    }
+
+    /// Tiles as `scale` sized squares, runs of the same colour in a row are merged into one
+    /// rectangle.
+    pub fn to_svg(&self, scale: usize) -> String {
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" \
+             viewBox=\"0 0 {width} {height}\" shape-rendering=\"crispEdges\">\n",
+            width * scale,
+            height * scale
+        );
+        svg += &format!(
+            "<rect width=\"{width}\" height=\"{height}\" fill=\"{}\"/>\n",
+            hex(BACKGROUND)
+        );
+        for row in 0..height {
+            let mut col = 0;
+            while col < width {
+                let color = self.rgb_at((row, col));
+                let start = col;
+                while col < width && self.rgb_at((row, col)) == color {
+                    col += 1;
+                }
+                if color != BACKGROUND {
+                    svg += &format!(
+                        "<rect x=\"{start}\" y=\"{row}\" width=\"{}\" height=\"1\" fill=\"{}\"/>\n",
+                        col - start,
+                        hex(color)
+                    );
+                }
+            }
+        }
+        if let Some(outline) = &self.outline {
+            let points: Vec<String> = outline
+                .iter()
+                .map(|point| format!("{}.5,{}.5", point.col, point.row))
+                .collect();
+            svg += &format!(
+                "<polygon points=\"{}\" fill=\"none\" stroke=\"black\" stroke-width=\"0.25\"/>\n",
+                points.join(" ")
+            );
+        }
+        svg += "</svg>\n";
+        svg
+    }
+}
+
+/// The day 14 platform with its rounded rocks highlighted.
+pub(crate) fn platform_scene(platform: Grid<char>) -> Scene {
+    let rocks = platform.iter().filter(|(_, symbol)| **symbol == 'O');
+    Scene {
+        overlays: vec![Overlay::new(
+            "rounded rocks",
+            Color::Yellow,
+            'O',
+            rocks.map(|(position, _)| position),
+        )],
+        grid: platform,
+        outline: None,
+    }
+}
+
+/// The day 21 garden with the `reachable` plots highlighted.
+pub(crate) fn garden_scene(
+    garden: Grid<char>,
+    reachable: impl IntoIterator<Item = (usize, usize)>,
+) -> Scene {
+    Scene {
+        grid: garden,
+        overlays: vec![Overlay::new("reachable", Color::Green, 'O', reachable)],
+        outline: None,
+    }
+}
+
+/// The days [`scene`] can draw.
+pub const DAYS: [u32; 8] = [10, 11, 14, 16, 17, 18, 21, 23];
+
+/// The scene of `day` for the puzzle text `input`, `None` for days without one.
+///
+/// `steps` are the steps of the elf of day 21 and the spin cycles of day 14, they default to the
+/// ones of the puzzle.
+pub fn scene(day: u32, input: &str, steps: Option<usize>) -> Option<Result<Scene, ParseError>> {
+    let scene = match day {
+        10 => day10::parse(input).map(|map| {
+            let pipe_loop =
+                Overlay::from_points("loop", Color::Yellow, '*', day10::find_pipe_loop(&map));
+            let enclosed = Overlay::new(
+                "enclosed",
+                Color::Green,
+                'I',
+                day10::find_enclosed_tiles(&map),
+            );
+            Scene {
+                grid: map,
+                overlays: vec![pipe_loop, enclosed],
+                outline: None,
+            }
+        }),
+        11 => day11::parse(input).map(|universe| {
+            let expanded = day11::expand(&universe, 2);
+            let galaxies = expanded.iter().filter(|(_, symbol)| **symbol == '#');
+            Scene {
+                overlays: vec![Overlay::new(
+                    "galaxies",
+                    Color::Yellow,
+                    '#',
+                    galaxies.map(|(position, _)| position),
+                )],
+                grid: expanded,
+                outline: None,
+            }
+        }),
+        14 => day14::parse(input).map(|platform| {
+            let cycles = steps.unwrap_or(1000000000);
+            platform_scene(day14::platform_after(&platform, cycles))
+        }),
+        16 => day16::parse(input).map(|layout| {
+            let energized = day16::energized_tiles(&layout, Point::new(0, 0), Dir::Right);
+            Scene {
+                overlays: vec![Overlay::from_points(
+                    "energized",
+                    Color::Yellow,
+                    '#',
+                    energized,
+                )],
+                grid: layout,
+                outline: None,
+            }
+        }),
+        17 => day17::parse(input).map(|layout| {
+            let (path, _) = day17::find_best_path(&layout, false);
+            Scene {
+                grid: layout.map(|heat_loss| {
+                    char::from_digit(*heat_loss as u32, 10)
+                        .expect("heat loss is checked while parsing")
+                }),
+                overlays: vec![Overlay::from_points("crucible path", Color::Red, '*', path)],
+                outline: None,
+            }
+        }),
+        18 => day18::parse(input).map(|instructions| {
+            let trench = day18::dig_trench(&instructions);
+            let lagoon = day10::enclosed_spaces(&trench);
+            let dug = trench.iter().filter(|(_, symbol)| **symbol != '.');
+            let corners = day18::trench_corners(&instructions);
+            let top_left = Point::new(
+                corners.iter().map(|corner| corner.row).min().unwrap_or(0),
+                corners.iter().map(|corner| corner.col).min().unwrap_or(0),
+            );
+            Scene {
+                overlays: vec![
+                    Overlay::new("trench", Color::Red, '#', dug.map(|(position, _)| position)),
+                    Overlay::new("lagoon", Color::Blue, '~', lagoon),
+                ],
+                grid: trench.map(|_| '.'),
+                outline: Some(corners.iter().map(|corner| *corner - top_left).collect()),
+            }
+        }),
+        21 => day21::parse(input).map(|garden| {
+            let reachable = day21::reachable_plots(&garden, steps.unwrap_or(64), &mut |_| {});
+            garden_scene(garden, reachable)
+        }),
+        23 => day23::parse(input).map(|layout| {
+            let hike = day23::find_longest_hike(&layout);
+            Scene {
+                grid: layout,
+                overlays: vec![Overlay::from_points("longest hike", Color::Blue, 'O', hike)],
+                outline: None,
+            }
+        }),
+        _ => return None,
+    };
+    Some(scene)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input;
+
+    #[test]
+    fn render_day10_loop_and_enclosed_tiles() {
+        let scene = scene(10, ".....\n.S-7.\n.|.|.\n.L-J.\n.....", None)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            ".....\n.***.\n.*I*.\n.***.\n.....\n\n* loop (8 tiles)\nI enclosed (1 tiles)\n",
+            scene.to_plain()
+        );
+        assert!(scene.to_ansi().contains("\x1b[30;43mS\x1b[0m"));
+        assert!(scene.to_ansi().contains("\x1b[30;42m.\x1b[0m"));
+    }
+
+    #[test]
+    fn render_every_day_example() {
+        for day in DAYS {
+            let example = input::read_file(&format!("./src/day{day}/example_input.txt"));
+            let scene = scene(day, &example, None).unwrap().unwrap();
+            assert!(scene
+                .overlays
+                .iter()
+                .all(|overlay| !overlay.positions.is_empty()));
+            assert_eq!(
+                scene.grid.height() + 1 + scene.overlays.len(),
+                scene.to_plain().lines().count()
+            );
+        }
+        assert!(scene(1, "", None).is_none());
+    }
+
+    #[test]
+    fn render_day21_steps() {
+        let example = input::read_file("./src/day21/example_input.txt");
+        let scene = scene(21, &example, Some(6)).unwrap().unwrap();
+        assert_eq!(16, scene.overlays[0].positions.len());
+    }
+
+    #[test]
+    fn render_day18_images() {
+        let example = input::read_file("./src/day18/example_input.txt");
+        let scene = scene(18, &example, None).unwrap().unwrap();
+        let tiles: usize = scene
+            .overlays
+            .iter()
+            .map(|overlay| overlay.positions.len())
+            .sum();
+        assert_eq!(62, tiles);
+
+        let image = scene.to_image(3);
+        assert_eq!((21, 30), (image.width(), image.height()));
+        assert_eq!(Color::Red.rgb(), image.get(0, 0));
+        assert_eq!(Color::Blue.rgb(), image.get(4, 4));
+
+        let svg = scene.to_svg(3);
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"21\" height=\"30\"")
+        );
+        assert!(svg.contains("<polygon points=\"0.5,0.5 6.5,0.5 6.5,5.5 "));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+}
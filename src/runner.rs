@@ -0,0 +1,447 @@
+use std::{
+    any::Any,
+    cmp::Reverse,
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use rayon::prelude::*;
+
+use crate::{
+    day1::{self, Vocabulary},
+    info,
+    input::{self, ParseError},
+    json::Json,
+    solution::{get_solution, Part, Puzzle, SOLUTIONS},
+};
+
+#[derive(Debug)]
+pub enum RunError {
+    UnknownDay(u32),
+    UnknownPart(u32),
+    Unsolved(u32, Part),
+    MissingInput(PathBuf),
+    InvalidInput(ParseError),
+    Panicked(u32, Part, String),
+}
+
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunError::UnknownDay(day) => {
+                write!(
+                    f,
+                    "day {day} does not exist, expected 1-{}",
+                    SOLUTIONS.len()
+                )
+            }
+            RunError::UnknownPart(part) => write!(f, "part {part} does not exist, expected 1 or 2"),
+            RunError::Unsolved(day, part) => write!(f, "day {day} part {part} is not solved yet"),
+            RunError::MissingInput(path) => write!(f, "cannot read input {}", path.display()),
+            RunError::InvalidInput(error) => write!(f, "invalid input, {error}"),
+            RunError::Panicked(day, part, message) => {
+                write!(f, "day {day} part {part} panicked: {message}")
+            }
+        }
+    }
+}
+
+pub fn default_input(day: u32) -> PathBuf {
+    PathBuf::from(format!("./src/day{day}/input.txt"))
+}
+
+/// The answer to one part of a run, `None` while the part is not solved.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub day: u32,
+    pub part: Part,
+    pub answer: Option<String>,
+    pub input: PathBuf,
+    /// Shared by all parts of the run, the input is parsed once.
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+/// Parses the input once and solves the given parts in order, timing both steps. Reading and
+/// parsing the input fail the whole run, every part has its own result.
+pub fn run(
+    day: u32,
+    parts: &[Part],
+    input: &Path,
+) -> Result<Vec<Result<Report, RunError>>, RunError> {
+    let solution = get_solution(day).ok_or(RunError::UnknownDay(day))?;
+    run_puzzle(solution, parts, input)
+}
+
+/// [`run`] for a puzzle outside of [`SOLUTIONS`], such as a day with other parameters.
+///
+/// A panic while parsing or solving is returned as [`RunError::Panicked`], parsing counts towards
+/// the first part and a panicking part leaves the others solved. The panic hook still reports the
+/// panic, replace it to keep the output clean.
+pub fn run_puzzle(
+    solution: &dyn Puzzle,
+    parts: &[Part],
+    input: &Path,
+) -> Result<Vec<Result<Report, RunError>>, RunError> {
+    let day = solution.day();
+    let text = input::read(input).map_err(|_| RunError::MissingInput(input.to_owned()))?;
+    let first_part = parts.first().copied().unwrap_or(Part::One);
+    let start = Instant::now();
+    let parsed =
+        catch_panic(day, first_part, || solution.parse(&text))?.map_err(RunError::InvalidInput)?;
+    let parse_time = start.elapsed();
+    info!("day {day}: parsed {} in {parse_time:.2?}", input.display());
+    Ok(parts
+        .iter()
+        .map(|part| {
+            let start = Instant::now();
+            let answer = catch_panic(day, *part, || solution.solve(parsed.as_ref(), *part))?;
+            Ok(Report {
+                day,
+                part: *part,
+                answer,
+                input: input.to_owned(),
+                parse_time,
+                solve_time: start.elapsed(),
+            })
+        })
+        .collect())
+}
+
+// The result of `f`, a panic becomes the error of `day` and `part`.
+fn catch_panic<T>(day: u32, part: Part, f: impl FnOnce() -> T) -> Result<T, RunError> {
+    panic::catch_unwind(AssertUnwindSafe(f))
+        .map_err(|payload| RunError::Panicked(day, part, panic_message(payload.as_ref())))
+}
+
+/// [`run`] for day 1 that decodes the input while it is read instead of holding it in memory.
+/// Part two reads the digits spelled in `vocabulary`. All parts share the one pass over the input,
+/// which is timed as parsing.
+pub fn run_day1(
+    parts: &[Part],
+    input: &Path,
+    vocabulary: &Vocabulary,
+) -> Result<Vec<Result<Report, RunError>>, RunError> {
+    let missing_input = |_| RunError::MissingInput(input.to_owned());
+    let reader = input::open(input).map_err(missing_input)?;
+    let no_words = Vocabulary::default();
+    let vocabularies: Vec<&Vocabulary> = parts
+        .iter()
+        .map(|part| match part {
+            Part::One => &no_words,
+            Part::Two => vocabulary,
+        })
+        .collect();
+    let start = Instant::now();
+    let sums = day1::solve_reader_each(reader, &vocabularies).map_err(missing_input)?;
+    let parse_time = start.elapsed();
+    info!("day 1: decoded {} in {parse_time:.2?}", input.display());
+    Ok(parts
+        .iter()
+        .zip(sums)
+        .map(|(part, sum)| {
+            Ok(Report {
+                day: 1,
+                part: *part,
+                answer: Some(sum.to_string()),
+                input: input.to_owned(),
+                parse_time,
+                solve_time: Duration::ZERO,
+            })
+        })
+        .collect())
+}
+
+/// Parses the input once and solves the given parts in order, `None` marks an unsolved part.
+pub fn solve(
+    day: u32,
+    parts: &[Part],
+    input: &Path,
+) -> Result<Vec<(Part, Result<Option<String>, RunError>)>, RunError> {
+    Ok(run(day, parts, input)?
+        .into_iter()
+        .zip(parts)
+        .map(|(result, part)| (*part, result.map(|report| report.answer)))
+        .collect())
+}
+
+/// One day and part of [`run_all`].
+pub struct Task {
+    pub day: u32,
+    pub part: Part,
+    pub result: Result<Report, RunError>,
+    /// Wall time of the task, reading the input included.
+    pub elapsed: Duration,
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.to_owned()
+    } else {
+        "unknown panic".to_owned()
+    }
+}
+
+/// Solves every part of every day on its own default input in parallel, slowest first.
+///
+/// Each task parses its input separately, a panicking solver only fails its own task. The panic
+/// hook still reports the panic, replace it to keep the output clean.
+pub fn run_all(days: &[u32], parts: &[Part]) -> Vec<Task> {
+    let tasks: Vec<(u32, Part)> = days
+        .iter()
+        .flat_map(|day| parts.iter().map(move |part| (*day, *part)))
+        .collect();
+    let mut tasks: Vec<Task> = tasks
+        .into_par_iter()
+        .map(|(day, part)| {
+            let start = Instant::now();
+            let result =
+                run(day, &[part], &default_input(day)).and_then(|mut results| results.remove(0));
+            Task {
+                day,
+                part,
+                result,
+                elapsed: start.elapsed(),
+            }
+        })
+        .collect();
+    tasks.sort_by_key(|task| Reverse(task.elapsed));
+    tasks
+}
+
+pub fn format_tasks(tasks: &[Task]) -> String {
+    let mut table = format!(
+        "{:>3}  {:>4}  {:>10}  {:>10}  answer\n",
+        "day", "part", "parse", "solve"
+    );
+    for task in tasks {
+        let (parse, solve, answer) = match &task.result {
+            Ok(report) => (
+                format!("{:.2?}", report.parse_time),
+                format!("{:.2?}", report.solve_time),
+                report
+                    .answer
+                    .clone()
+                    .unwrap_or_else(|| "not solved yet".to_owned()),
+            ),
+            Err(error) => ("-".to_owned(), "-".to_owned(), format!("error: {error}")),
+        };
+        table += &format!(
+            "{:>3}  {:>4}  {parse:>10}  {solve:>10}  {answer}\n",
+            task.day,
+            task.part.to_string(),
+        );
+    }
+    let total: Duration = tasks.iter().map(|task| task.elapsed).sum();
+    let failed = tasks.iter().filter(|task| task.result.is_err()).count();
+    table += &format!(
+        "\n{} tasks, {failed} failed, {total:.2?} of work\n",
+        tasks.len()
+    );
+    table
+}
+
+/// How the answers of a run are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Format> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// One object per report, durations in nanoseconds like the benchmark baselines.
+pub fn to_json(reports: &[Report]) -> Json {
+    Json::Array(
+        reports
+            .iter()
+            .map(|report| {
+                Json::Object(vec![
+                    ("day".to_owned(), Json::Number(report.day as f64)),
+                    ("part".to_owned(), Json::Number(report.part.number() as f64)),
+                    (
+                        "answer".to_owned(),
+                        report.answer.clone().map_or(Json::Null, Json::String),
+                    ),
+                    (
+                        "input".to_owned(),
+                        Json::String(report.input.display().to_string()),
+                    ),
+                    (
+                        "parse_ns".to_owned(),
+                        Json::Number(report.parse_time.as_nanos() as f64),
+                    ),
+                    (
+                        "solve_ns".to_owned(),
+                        Json::Number(report.solve_time.as_nanos() as f64),
+                    ),
+                ])
+            })
+            .collect(),
+    )
+}
+
+// Quotes fields containing separators, quotes or line breaks.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// A header line and one line per report, an unsolved part has an empty answer.
+pub fn format_csv(reports: &[Report]) -> String {
+    let mut csv = "day,part,answer,input,parse_ns,solve_ns\n".to_owned();
+    for report in reports {
+        csv += &format!(
+            "{},{},{},{},{},{}\n",
+            report.day,
+            report.part,
+            csv_field(report.answer.as_deref().unwrap_or("")),
+            csv_field(&report.input.display().to_string()),
+            report.parse_time.as_nanos(),
+            report.solve_time.as_nanos(),
+        );
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_reports_answers_as_json_and_csv() {
+        let input = Path::new("./src/day6/example_input.txt");
+        let mut reports: Vec<Report> = run(6, &[Part::One, Part::Two], input)
+            .unwrap()
+            .into_iter()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            vec![Some("288".to_owned()), Some("71503".to_owned())],
+            reports
+                .iter()
+                .map(|report| report.answer.clone())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(reports[0].parse_time, reports[1].parse_time);
+
+        for report in &mut reports {
+            report.parse_time = Duration::from_nanos(5);
+            report.solve_time = Duration::from_nanos(7);
+        }
+        reports[1].answer = Some("a,\"b\"".to_owned());
+        assert_eq!(
+            "day,part,answer,input,parse_ns,solve_ns\n\
+             6,1,288,./src/day6/example_input.txt,5,7\n\
+             6,2,\"a,\"\"b\"\"\",./src/day6/example_input.txt,5,7\n",
+            format_csv(&reports)
+        );
+        let json = to_json(&reports);
+        let first = &json.as_array().unwrap()[0];
+        assert_eq!(Some(1.0), first.get("part").and_then(Json::as_f64));
+        assert_eq!(Some("288"), first.get("answer").and_then(Json::as_str));
+        assert_eq!(Some(5.0), first.get("parse_ns").and_then(Json::as_f64));
+    }
+
+    #[test]
+    fn run_all_reports_every_task() {
+        let tasks = run_all(&[6, 25], &[Part::One, Part::Two]);
+        assert_eq!(4, tasks.len());
+        assert!(tasks
+            .windows(2)
+            .all(|pair| pair[0].elapsed >= pair[1].elapsed));
+        let answer = |part: Part| {
+            let task = tasks
+                .iter()
+                .find(|task| task.day == 6 && task.part == part)
+                .unwrap();
+            task.result.as_ref().unwrap().answer.clone().unwrap()
+        };
+        assert_eq!(
+            ("1159152", "41513103"),
+            (&*answer(Part::One), &*answer(Part::Two))
+        );
+        assert!(tasks
+            .iter()
+            .filter(|task| task.day == 25)
+            .all(|task| matches!(task.result, Err(RunError::UnknownDay(25)))));
+
+        let table = format_tasks(&tasks);
+        assert!(table.contains("error: day 25 does not exist"));
+        assert!(table.contains("4 tasks, 2 failed"));
+    }
+
+    #[test]
+    fn run_day1_decodes_while_reading() {
+        let input = Path::new("./src/day1/input.txt");
+        let answers: Vec<Option<String>> =
+            run_day1(&[Part::Two, Part::One], input, &Vocabulary::english())
+                .unwrap()
+                .into_iter()
+                .map(|result| result.unwrap().answer)
+                .collect();
+        assert_eq!(
+            vec![Some("54845".to_owned()), Some("55090".to_owned())],
+            answers
+        );
+        assert!(matches!(
+            run_day1(
+                &[Part::One],
+                Path::new("./missing.txt"),
+                &Vocabulary::english()
+            ),
+            Err(RunError::MissingInput(_))
+        ));
+    }
+
+    // Day 6 that panics on its second part.
+    struct Faulty;
+
+    impl Puzzle for Faulty {
+        fn day(&self) -> u32 {
+            6
+        }
+
+        fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+            Ok(Box::new(input.to_owned()))
+        }
+
+        fn solve(&self, _: &dyn Any, part: Part) -> Option<String> {
+            match part {
+                Part::One => Some("288".to_owned()),
+                Part::Two => panic!("no second part"),
+            }
+        }
+    }
+
+    #[test]
+    fn run_puzzle_reports_panics_next_to_answers() {
+        let input = Path::new("./src/day6/example_input.txt");
+        let mut results = run_puzzle(&Faulty, &[Part::One, Part::Two], input).unwrap();
+        let Err(RunError::Panicked(6, Part::Two, message)) = results.pop().unwrap() else {
+            panic!("expected a panic of part two");
+        };
+        assert_eq!("no second part", message);
+        assert_eq!(
+            Some("288".to_owned()),
+            results.pop().unwrap().unwrap().answer
+        );
+    }
+}
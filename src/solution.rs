@@ -0,0 +1,160 @@
+use std::{any::Any, fmt};
+
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day24, day3, day4, day5, day6, day7, day8, day9, input::ParseError,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn from_number(number: u32) -> Option<Part> {
+        match number {
+            1 => Some(Part::One),
+            2 => Some(Part::Two),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+/// A day of the calendar: the input is parsed once and both parts are solved from it.
+///
+/// Puzzle parameters such as the expansion factor of day 11 or the step count of day 21 are
+/// fixed to the values of the actual puzzle, the day modules expose them separately.
+pub trait Solution {
+    const DAY: u32;
+    type Input: 'static;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part_one(input: &Self::Input) -> String;
+    /// `None` while part two is not solved.
+    fn part_two(input: &Self::Input) -> Option<String>;
+}
+
+/// Object safe view on a [`Solution`], used to iterate over all days.
+pub trait Puzzle: Sync {
+    fn day(&self) -> u32;
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError>;
+    /// Panics if `input` was not parsed by this puzzle.
+    fn solve(&self, input: &dyn Any, part: Part) -> Option<String>;
+}
+
+impl<S: Solution + Sync> Puzzle for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn parse(&self, input: &str) -> Result<Box<dyn Any>, ParseError> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn solve(&self, input: &dyn Any, part: Part) -> Option<String> {
+        let input = input
+            .downcast_ref::<S::Input>()
+            .unwrap_or_else(|| panic!("Input was not parsed by day {}", S::DAY));
+        match part {
+            Part::One => Some(S::part_one(input)),
+            Part::Two => S::part_two(input),
+        }
+    }
+}
+
+pub static SOLUTIONS: [&dyn Puzzle; 24] = [
+    &day1::Day1,
+    &day2::Day2,
+    &day3::Day3,
+    &day4::Day4,
+    &day5::Day5,
+    &day6::Day6,
+    &day7::Day7,
+    &day8::Day8,
+    &day9::Day9,
+    &day10::Day10,
+    &day11::Day11,
+    &day12::Day12,
+    &day13::Day13,
+    &day14::Day14,
+    &day15::Day15,
+    &day16::Day16,
+    &day17::Day17,
+    &day18::Day18,
+    &day19::Day19,
+    &day20::Day20,
+    &day21::Day21,
+    &day22::Day22,
+    &day23::Day23,
+    &day24::Day24,
+];
+
+pub fn get_solution(day: u32) -> Option<&'static dyn Puzzle> {
+    SOLUTIONS
+        .iter()
+        .find(|solution| solution.day() == day)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        let days: Vec<u32> = SOLUTIONS.iter().map(|solution| solution.day()).collect();
+        assert_eq!((1..=24).collect::<Vec<u32>>(), days);
+    }
+
+    #[test]
+    fn registry_solves_parsed_input() {
+        let solution = get_solution(6).unwrap();
+        let input = std::fs::read_to_string("./src/day6/example_input.txt").unwrap();
+        let input = solution.parse(&input).unwrap();
+        assert_eq!(
+            Some("288".to_owned()),
+            solution.solve(input.as_ref(), Part::One)
+        );
+        assert_eq!(
+            Some("71503".to_owned()),
+            solution.solve(input.as_ref(), Part::Two)
+        );
+    }
+
+    #[test]
+    fn registry_reports_unsolved_part() {
+        let solution = get_solution(24).unwrap();
+        let input = solution.parse("19, 13, 30 @ -2,  1, -2").unwrap();
+        assert_eq!(None, solution.solve(input.as_ref(), Part::Two));
+    }
+
+    #[test]
+    fn registry_reports_parse_errors() {
+        let solution = get_solution(18).unwrap();
+        let error = solution
+            .parse("R 6 (#70c710)\nX 5 (#0dc571)")
+            .err()
+            .unwrap();
+        assert_eq!(
+            "day 18, line 2, column 1: expected a direction U, D, L or R, found 'X'",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,233 @@
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{runner, solution::Part};
+
+pub fn default_manifest() -> PathBuf {
+    PathBuf::from("./answers.txt")
+}
+
+/// One line of the answer manifest: `day part input answer`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entry {
+    pub day: u32,
+    pub part: Part,
+    pub input: PathBuf,
+    pub answer: String,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ManifestError {
+    Unreadable(PathBuf),
+    Invalid(usize, String),
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ManifestError::Unreadable(path) => write!(f, "cannot read manifest {}", path.display()),
+            ManifestError::Invalid(line, reason) => write!(f, "manifest line {line}: {reason}"),
+        }
+    }
+}
+
+fn parse_entry(line: &str) -> Result<Entry, String> {
+    let fields: Vec<&str> = line.split_whitespace().collect();
+    let [day, part, input, answer] = fields[..] else {
+        return Err(format!("expected 'day part input answer', found '{line}'"));
+    };
+    let day = day
+        .parse::<u32>()
+        .map_err(|_| format!("expected a day, found '{day}'"))?;
+    let part = part
+        .parse::<u32>()
+        .ok()
+        .and_then(Part::from_number)
+        .ok_or_else(|| format!("expected part 1 or 2, found '{part}'"))?;
+    Ok(Entry {
+        day,
+        part,
+        input: PathBuf::from(input),
+        answer: answer.to_owned(),
+    })
+}
+
+/// Blank lines and lines starting with `#` are skipped.
+pub fn parse_manifest(manifest: &str) -> Result<Vec<Entry>, ManifestError> {
+    manifest
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'))
+        .map(|(index, line)| {
+            parse_entry(line).map_err(|reason| ManifestError::Invalid(index + 1, reason))
+        })
+        .collect()
+}
+
+pub fn load_manifest(path: &Path) -> Result<Vec<Entry>, ManifestError> {
+    let manifest =
+        fs::read_to_string(path).map_err(|_| ManifestError::Unreadable(path.to_owned()))?;
+    parse_manifest(&manifest)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail(String),
+    Unsolved,
+    Error(String),
+}
+
+impl Status {
+    pub fn is_pass(&self) -> bool {
+        *self == Status::Pass
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail(_) => write!(f, "FAIL"),
+            Status::Unsolved => write!(f, "UNSOLVED"),
+            Status::Error(_) => write!(f, "ERROR"),
+        }
+    }
+}
+
+pub struct Outcome {
+    pub entry: Entry,
+    pub status: Status,
+}
+
+/// Solves every entry, each input is parsed once for all parts listed with it.
+pub fn verify(entries: &[Entry]) -> Vec<Outcome> {
+    let mut groups: Vec<(u32, &Path, Vec<Part>)> = Vec::new();
+    for entry in entries {
+        match groups
+            .iter_mut()
+            .find(|(day, input, _)| *day == entry.day && *input == entry.input)
+        {
+            Some((_, _, parts)) => parts.push(entry.part),
+            None => groups.push((entry.day, &entry.input, vec![entry.part])),
+        }
+    }
+
+    let mut answers: Vec<(u32, &Path, Part, Result<Option<String>, String>)> = Vec::new();
+    for (day, input, parts) in groups {
+        match runner::solve(day, &parts, input) {
+            Ok(solved) => answers.extend(solved.into_iter().map(|(part, answer)| {
+                (day, input, part, answer.map_err(|error| error.to_string()))
+            })),
+            Err(error) => answers.extend(
+                parts
+                    .into_iter()
+                    .map(|part| (day, input, part, Err(error.to_string()))),
+            ),
+        }
+    }
+
+    entries
+        .iter()
+        .map(|entry| {
+            let (_, _, _, answer) = answers
+                .iter()
+                .find(|(day, input, part, _)| {
+                    *day == entry.day && *input == entry.input && *part == entry.part
+                })
+                .unwrap();
+            let status = match answer {
+                Ok(Some(answer)) if *answer == entry.answer => Status::Pass,
+                Ok(Some(answer)) => Status::Fail(answer.to_owned()),
+                Ok(None) => Status::Unsolved,
+                Err(error) => Status::Error(error.to_owned()),
+            };
+            Outcome {
+                entry: entry.clone(),
+                status,
+            }
+        })
+        .collect()
+}
+
+pub fn format_outcomes(outcomes: &[Outcome]) -> String {
+    let input_width = outcomes
+        .iter()
+        .map(|outcome| outcome.entry.input.display().to_string().len())
+        .max()
+        .unwrap_or(0)
+        .max("input".len());
+    let mut table = format!(
+        "{:>3}  {:>4}  {:<input_width$}  {:<8}  expected / actual\n",
+        "day", "part", "input", "result"
+    );
+    for Outcome { entry, status } in outcomes {
+        let diff = match status {
+            Status::Pass => entry.answer.to_owned(),
+            Status::Fail(actual) => format!("{} / {actual}", entry.answer),
+            Status::Unsolved => format!("{} / -", entry.answer),
+            Status::Error(error) => format!("{} / {error}", entry.answer),
+        };
+        table += &format!(
+            "{:>3}  {:>4}  {:<input_width$}  {:<8}  {diff}\n",
+            entry.day,
+            entry.part.to_string(),
+            entry.input.display(),
+            status.to_string(),
+        );
+    }
+    let passed = outcomes
+        .iter()
+        .filter(|outcome| outcome.status.is_pass())
+        .count();
+    table += &format!("\n{passed} of {} answers match\n", outcomes.len());
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_skips_comments_and_reports_bad_lines() {
+        let manifest = "# day part input answer\n\n6 1 src/day6/example_input.txt 288\n";
+        assert_eq!(
+            Ok(vec![Entry {
+                day: 6,
+                part: Part::One,
+                input: PathBuf::from("src/day6/example_input.txt"),
+                answer: "288".to_owned(),
+            }]),
+            parse_manifest(manifest)
+        );
+        assert_eq!(
+            Err(ManifestError::Invalid(
+                2,
+                "expected part 1 or 2, found '3'".to_owned()
+            )),
+            parse_manifest("# comment\n6 3 src/day6/example_input.txt 288")
+        );
+        assert!(parse_manifest("6 1 src/day6/example_input.txt").is_err());
+    }
+
+    #[test]
+    fn verify_reports_pass_fail_and_errors() {
+        let manifest = "6 1 src/day6/example_input.txt 288
+                        6 2 src/day6/example_input.txt 71504
+                        6 1 src/day6/missing.txt 288";
+        let outcomes = verify(&parse_manifest(manifest).unwrap());
+        let statuses: Vec<Status> = outcomes.into_iter().map(|outcome| outcome.status).collect();
+        assert_eq!(Status::Pass, statuses[0]);
+        assert_eq!(Status::Fail("71503".to_owned()), statuses[1]);
+        assert!(matches!(statuses[2], Status::Error(_)));
+    }
+
+    #[test]
+    fn manifest_lists_existing_inputs() {
+        for entry in load_manifest(&default_manifest()).unwrap() {
+            assert!(entry.input.exists(), "{} is missing", entry.input.display());
+        }
+    }
+}